@@ -1,33 +1,51 @@
-use agama_lib::auth::AuthToken;
+use agama_lib::{
+    auth::{AuthToken, UserTokens},
+    base_http_client::BaseHTTPClient,
+};
 use clap::Subcommand;
 
-use crate::error::CliError;
+use crate::{error::CliError, output::OutputFormat};
 use inquire::Password;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
 use std::io::{self, IsTerminal};
 
-const DEFAULT_AUTH_URL: &str = "http://localhost/api/auth";
-
 #[derive(Subcommand, Debug)]
 pub enum AuthCommands {
     /// Authenticate with Agama's server and store the token.
     ///
     /// This command tries to get the password from the standard input. If it is not there, it asks
-    /// the user interactively. Upon successful login, it stores the token in .agama/agama-jwt. The
-    /// token will be automatically sent to authenticate the following requests.
+    /// the user interactively. Upon successful login, it stores the token in
+    /// ~/.local/agama/tokens.json. The token will be automatically sent to authenticate the
+    /// following requests to the same server.
+    ///
+    /// Use the "--host" option to log into a remote server. It is possible to be logged into
+    /// several servers at the same time.
     Login,
-    /// Deauthenticate by removing the token.
+    /// Deauthenticate by removing the token for the server.
     Logout,
-    /// Print the used token to the standard output.
+    /// Print the token used for the server to the standard output.
     Show,
+    /// List the servers you are logged into.
+    List,
 }
 
 /// Main entry point called from agama CLI main loop
-pub async fn run(subcommand: AuthCommands) -> anyhow::Result<()> {
+pub async fn run(
+    subcommand: AuthCommands,
+    client: BaseHTTPClient,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
     match subcommand {
-        AuthCommands::Login => login(read_password()?).await,
-        AuthCommands::Logout => logout(),
-        AuthCommands::Show => show(),
+        AuthCommands::Login => {
+            login(client, read_password()?).await?;
+            format.print(&(), || {})
+        }
+        AuthCommands::Logout => {
+            logout(&client.base_url)?;
+            format.print(&(), || {})
+        }
+        AuthCommands::Show => show(&client.base_url, format),
+        AuthCommands::List => list(format),
     }
 }
 
@@ -57,56 +75,64 @@ fn ask_password() -> Result<String, CliError> {
         .map_err(CliError::InteractivePassword)
 }
 
-/// Necessary http request header for authenticate
-fn authenticate_headers() -> HeaderMap {
-    let mut headers = HeaderMap::new();
-
-    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+#[derive(Serialize)]
+struct LoginRequest {
+    password: String,
+}
 
-    headers
+#[derive(Deserialize)]
+struct LoginResponse {
+    token: String,
 }
 
 /// Query web server for JWT
-async fn get_jwt(url: String, password: String) -> anyhow::Result<String> {
-    let client = reqwest::Client::new();
-    let response = client
-        .post(url)
-        .headers(authenticate_headers())
-        .body(format!("{{\"password\": \"{}\"}}", password))
-        .send()
-        .await?;
-    let body = response
-        .json::<std::collections::HashMap<String, String>>()
-        .await?;
-    let value = body.get("token");
-
-    if let Some(token) = value {
-        return Ok(token.clone());
-    }
-
-    Err(anyhow::anyhow!("Failed to get authentication token"))
+async fn get_jwt(client: &BaseHTTPClient, password: String) -> anyhow::Result<String> {
+    let request = LoginRequest { password };
+    let response: LoginResponse = client.post("/auth", &request).await?;
+    Ok(response.token)
 }
 
 /// Logs into the installation web server and stores JWT for later use.
-async fn login(password: String) -> anyhow::Result<()> {
+///
+/// * `client`: HTTP client pointing to the selected server.
+/// * `password`: root password.
+async fn login(client: BaseHTTPClient, password: String) -> anyhow::Result<()> {
     // 1) ask web server for JWT
-    let res = get_jwt(DEFAULT_AUTH_URL.to_string(), password).await?;
+    let res = get_jwt(&client, password).await?;
     let token = AuthToken::new(&res);
-    Ok(token.write_user_token()?)
+    Ok(token.write_user_token_for(&client.base_url)?)
 }
 
 /// Releases JWT
-fn logout() -> anyhow::Result<()> {
-    Ok(AuthToken::remove_user_token()?)
+///
+/// * `server`: URL of the server's API.
+fn logout(server: &str) -> anyhow::Result<()> {
+    Ok(AuthToken::remove_user_token_for(server)?)
 }
 
 /// Shows stored JWT on stdout
-fn show() -> anyhow::Result<()> {
+///
+/// * `server`: URL of the server's API.
+/// * `format`: output format.
+fn show(server: &str, format: OutputFormat) -> anyhow::Result<()> {
     // we do not care if jwt() fails or not. If there is something to print, show it otherwise
     // stay silent
-    if let Some(token) = AuthToken::find() {
-        println!("{}", token.as_str());
-    }
+    let token = AuthToken::find_for(server);
+    let jwt = token.as_ref().map(|t| t.as_str());
+    format.print(&jwt, || {
+        if let Some(jwt) = jwt {
+            println!("{}", jwt);
+        }
+    })
+}
 
-    Ok(())
+/// Lists the servers with a stored JWT
+fn list(format: OutputFormat) -> anyhow::Result<()> {
+    let tokens = UserTokens::load()?;
+    let servers: Vec<_> = tokens.servers().collect();
+    format.print(&servers, || {
+        for server in &servers {
+            println!("{}", server);
+        }
+    })
 }
@@ -1,5 +1,6 @@
 use crate::auth::AuthCommands;
 use crate::config::ConfigCommands;
+use crate::events::EventsArgs;
 use crate::logs::LogsCommands;
 use crate::profile::ProfileCommands;
 use crate::questions::QuestionsCommands;
@@ -32,9 +33,26 @@ pub enum Commands {
     /// This command starts the installation process.  Beware it is a destructive operation because
     /// it will set up the storage devices, install the packages, etc.
     ///
-    /// When the preconditions for the installation are not met, it informs the user and returns,
-    /// making no changes to the system.
-    Install,
+    /// When the preconditions for the installation are not met, it informs the user about the
+    /// reasons (e.g., "storage: No target disk selected") and returns, making no changes to the
+    /// system.
+    ///
+    /// By default, the command follows the progress until the installation finishes. Use the
+    /// "--no-wait" option to return as soon as it starts.
+    Install {
+        /// Wait until the installation finishes, reporting the progress (default)
+        #[arg(long, overrides_with = "no_wait")]
+        wait: bool,
+        /// Return as soon as the installation starts
+        #[arg(long, overrides_with = "wait")]
+        no_wait: bool,
+    },
+
+    /// List the issues reported by the installer.
+    ///
+    /// The issues are problems found in the system or in the configuration (e.g., no product was
+    /// selected). The ones with "error" severity prevent the installation from starting.
+    Issues,
 
     /// Manage auto-installation profiles (retrieving, applying, etc.).
     #[command(subcommand)]
@@ -74,6 +92,13 @@ pub enum Commands {
     #[command(subcommand)]
     Auth(AuthCommands),
 
+    /// Print the events emitted by the installer.
+    ///
+    /// It connects to the server and prints the events as they come (e.g., progress, issues or
+    /// questions changes), which is useful to find out what the installer is doing. Use the
+    /// "--output json" option to get one event per line (NDJSON).
+    Events(EventsArgs),
+
     /// Download file from given URL
     ///
     /// The purpose of this command is to download files using AutoYaST supported schemas (e.g. device:// or relurl://).
@@ -4,11 +4,14 @@ use std::{
     process::Command,
 };
 
-use crate::show_progress;
+use crate::{error::CliError, output::OutputFormat, show_progress};
 use agama_lib::{
-    auth::AuthToken, connection, install_settings::InstallSettings, Store as SettingsStore,
+    base_http_client::BaseHTTPClient,
+    install_settings::{InstallSettings, InstallSettingsHTTPClient, SettingsDiff},
+    profile::ProfileEvaluator,
+    Section, Store,
 };
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use clap::Subcommand;
 use std::io::Write;
 use tempfile::Builder;
@@ -23,10 +26,39 @@ pub enum ConfigCommands {
     /// are not included in the output.
     ///
     /// The output of command can be used as input for the "agama config load".
-    Show,
+    ///
+    /// It is possible to limit the output to some sections (e.g., "agama config show network" or
+    /// "agama config show --only storage,software").
+    Show {
+        /// Sections to show (network, product, localization, software, users or storage)
+        sections: Vec<Section>,
+
+        /// Comma-separated list of sections to show
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<Section>,
+    },
 
-    /// Read and load a profile from the standard input.
-    Load,
+    /// Read and load a profile.
+    ///
+    /// By default, the profile is read from the standard input.
+    Load {
+        /// Apply all the settings or none of them
+        ///
+        /// If any section cannot be applied, the previous settings are restored and the failing
+        /// section is reported. A root password that replaced an existing one cannot be
+        /// restored, so the rollback fails in that case.
+        #[arg(long)]
+        atomic: bool,
+
+        /// Read the profile from the given file instead of the standard input
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Comma-separated list of sections to load (network, product, localization, software,
+        /// users or storage). The rest of the profile is ignored.
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<Section>,
+    },
 
     /// Edit and update installation option using an external editor.
     ///
@@ -39,46 +71,133 @@ pub enum ConfigCommands {
         #[arg(short, long)]
         editor: Option<String>,
     },
-}
 
-pub async fn run(subcommand: ConfigCommands) -> anyhow::Result<()> {
-    let Some(token) = AuthToken::find() else {
-        println!("You need to login for generating a valid token: agama auth login");
-        return Ok(());
-    };
+    /// Compare a profile with the current settings.
+    ///
+    /// It shows the settings that would change if the profile was loaded. The settings which are
+    /// not included in the profile are ignored. The values of the secrets (e.g., passwords) are
+    /// masked.
+    ///
+    /// The command exits with an error code if there are differences. In JSON mode
+    /// ("--output json"), they are included in the details of the error.
+    Diff {
+        /// Path to the profile (JSON or Jsonnet)
+        path: PathBuf,
+    },
+}
 
-    let client = agama_lib::http_client(token.as_str())?;
-    let store = SettingsStore::new(connection().await?, client).await?;
+/// Main entry point called from agama CLI main loop
+///
+/// * `subcommand`: config subcommand to run.
+/// * `client`: authenticated HTTP client.
+/// * `format`: output format.
+pub async fn run(
+    subcommand: ConfigCommands,
+    client: BaseHTTPClient,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let settings_client = InstallSettingsHTTPClient::new_with_base(client.clone());
 
     match subcommand {
-        ConfigCommands::Show => {
-            let model = store.load().await?;
+        ConfigCommands::Show { sections, only } => {
+            let sections = [sections, only].concat();
+            let model = if sections.is_empty() {
+                serde_json::to_value(settings_client.get_config().await?)?
+            } else {
+                let store = Store::new(client).await?;
+                without_nulls(&store.load_sections(&sections).await?)?
+            };
             let json = serde_json::to_string_pretty(&model)?;
-            println!("{}", json);
-            Ok(())
+            format.print(&model, || println!("{}", json))
         }
-        ConfigCommands::Load => {
-            let mut stdin = io::stdin();
-            let mut contents = String::new();
-            stdin.read_to_string(&mut contents)?;
-            let result: InstallSettings = serde_json::from_str(&contents)?;
-            Ok(store.store(&result).await?)
+        ConfigCommands::Load { atomic, from, only } => {
+            let mut result: InstallSettings = match from {
+                Some(path) => InstallSettings::from_file(&path)
+                    .context(format!("Could not read the profile {:?}", path))?,
+                None => {
+                    let mut stdin = io::stdin();
+                    let mut contents = String::new();
+                    stdin.read_to_string(&mut contents)?;
+                    serde_json::from_str(&contents)?
+                }
+            };
+
+            if only.is_empty() {
+                if atomic {
+                    settings_client.set_config_atomic(&result).await?;
+                } else {
+                    settings_client.set_config(&result).await?;
+                }
+            } else {
+                Section::retain(&mut result, &only);
+                let store = Store::new(client).await?;
+                if atomic {
+                    store.store_atomic(&result).await?;
+                } else {
+                    store.store(&result).await?;
+                }
+            }
+            format.print(&(), || {})
         }
         ConfigCommands::Edit { editor } => {
-            let model = store.load().await?;
+            let model = settings_client.get_config().await?;
             let editor = editor
                 .or_else(|| std::env::var("EDITOR").ok())
                 .unwrap_or(DEFAULT_EDITOR.to_string());
             let result = edit(&model, &editor)?;
             tokio::spawn(async move {
-                show_progress().await.unwrap();
+                show_progress(client, format).await.unwrap();
             });
-            store.store(&result).await?;
-            Ok(())
+            settings_client.set_config(&result).await?;
+            format.print(&(), || {})
+        }
+        ConfigCommands::Diff { path } => {
+            let profile = read_profile(&path)?;
+            let current = settings_client.get_config().await?;
+            let diff = SettingsDiff::new(&current, &profile)?;
+            if !format.is_json() {
+                print!("{}", diff);
+            }
+
+            if diff.is_empty() {
+                format.print(&diff, || {})
+            } else {
+                // in JSON mode, the differences are included in the error details
+                Err(CliError::SettingsDiffer(diff))?
+            }
         }
     }
 }
 
+/// Converts the installation settings to JSON, removing the sections that are not set.
+///
+/// * `model`: installation settings.
+fn without_nulls(model: &InstallSettings) -> anyhow::Result<serde_json::Value> {
+    let mut json = serde_json::to_value(model)?;
+    if let Some(sections) = json.as_object_mut() {
+        sections.retain(|_, value| !value.is_null());
+    }
+    Ok(json)
+}
+
+/// Reads the installation settings from a profile.
+///
+/// Jsonnet profiles are evaluated before reading them.
+///
+/// * `path`: path to the profile.
+fn read_profile(path: &PathBuf) -> anyhow::Result<InstallSettings> {
+    if path.extension().is_some_and(|e| e == "jsonnet") {
+        let mut output = vec![];
+        let evaluator = ProfileEvaluator {};
+        evaluator
+            .evaluate(path, &mut output)
+            .context("Could not evaluate the profile")?;
+        return Ok(serde_json::from_slice(&output)?);
+    }
+
+    InstallSettings::from_file(path).context(format!("Could not read the profile {:?}", path))
+}
+
 /// Edit the installation settings using an external editor.
 ///
 /// If the editor does not return a successful error code, it returns an error.
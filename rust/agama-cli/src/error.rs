@@ -1,3 +1,4 @@
+use agama_lib::{install_settings::SettingsDiff, manager::InstallationBlockers};
 use inquire::InquireError;
 use thiserror::Error;
 
@@ -5,10 +6,38 @@ use thiserror::Error;
 pub enum CliError {
     #[error("Cannot perform the installation as the settings are not valid")]
     Validation,
+    #[error("Cannot perform the installation:\n{0}")]
+    InstallationBlocked(InstallationBlockers),
     #[error("Could not start the installation")]
     Installation,
+    #[error("The profile differs from the current settings")]
+    SettingsDiffer(SettingsDiff),
     #[error("Could not read the password")]
     InteractivePassword(#[source] InquireError),
     #[error("Could not read the password from the standard input")]
     StdinPassword(#[source] std::io::Error),
 }
+
+impl CliError {
+    /// Returns an identifier for the kind of error.
+    ///
+    /// Like [agama_lib::error::ServiceError::code], it is stable, so programs can rely on it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Validation => "invalid_settings",
+            Self::InstallationBlocked(_) => "installation_blocked",
+            Self::Installation => "installation_failed",
+            Self::InteractivePassword(_) | Self::StdinPassword(_) => "password_input",
+            Self::SettingsDiffer(_) => "settings_differ",
+        }
+    }
+
+    /// Returns additional information about the error, if any.
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::SettingsDiffer(diff) => serde_json::to_value(diff).ok(),
+            Self::InstallationBlocked(blockers) => serde_json::to_value(blockers).ok(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::output::OutputFormat;
+use agama_lib::{
+    base_http_client::BaseHTTPClient,
+    events::{EventsClient, EventsFilter},
+};
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct EventsArgs {
+    /// Comma-separated list of event types to show (e.g., "Progress,IssuesChanged")
+    #[arg(long = "type", value_delimiter = ',')]
+    types: Vec<String>,
+
+    /// Comma-separated list of services to show the events from (e.g., "software,storage")
+    ///
+    /// The events that are not related to a service are not shown.
+    #[arg(long = "service", value_delimiter = ',')]
+    services: Vec<String>,
+}
+
+/// Main entry point called from agama CLI main loop
+///
+/// It prints the events until the server closes the connection.
+///
+/// * `args`: command arguments.
+/// * `client`: authenticated HTTP client.
+/// * `format`: output format. In JSON mode, it writes one event per line (NDJSON).
+pub async fn run(
+    args: EventsArgs,
+    client: BaseHTTPClient,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let filter = EventsFilter {
+        types: args.types,
+        services: args.services,
+    };
+    let mut events = EventsClient::connect(&client).await?;
+    events.subscribe(&filter).await?;
+
+    while let Some(event) = events.receive_json().await? {
+        // the server filters the events, but older versions ignore the subscription
+        if !filter.matches(&event) {
+            continue;
+        }
+
+        if format.is_json() {
+            println!("{}", serde_json::to_string(&event)?);
+        } else {
+            println!("{}", serde_json::to_string_pretty(&event)?);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::output::OutputFormat;
+use agama_lib::{
+    base_http_client::BaseHTTPClient, issue::IssuesByService, manager::ManagerHTTPClient,
+};
+
+/// Main entry point called from agama CLI main loop
+///
+/// It prints the issues of all the services, grouped by service.
+///
+/// * `client`: authenticated HTTP client.
+/// * `format`: output format.
+pub async fn run(client: BaseHTTPClient, format: OutputFormat) -> anyhow::Result<()> {
+    let manager = ManagerHTTPClient::new_with_base(client);
+    let issues = manager.issues().await?;
+    format.print(&issues, || print_issues(&issues))
+}
+
+/// Prints the issues in a human-readable way.
+///
+/// * `issues`: issues grouped by service.
+fn print_issues(issues: &IssuesByService) {
+    let mut found = false;
+    for (service, issues) in issues {
+        for issue in issues {
+            found = true;
+            println!("{}: [{}] {}", service, issue.severity, issue.description);
+            if let Some(details) = &issue.details {
+                println!("  {}", details);
+            }
+        }
+    }
+
+    if !found {
+        println!("No issues found.");
+    }
+}
@@ -1,7 +1,9 @@
+use crate::output::OutputFormat;
 use clap::Subcommand;
 use fs_extra::copy_items;
 use fs_extra::dir::CopyOptions;
 use nix::unistd::Uid;
+use serde::Serialize;
 use std::fs;
 use std::fs::File;
 use std::io;
@@ -28,8 +30,22 @@ pub enum LogsCommands {
     List,
 }
 
+/// Result of the "agama logs store" subcommand, as reported in JSON mode
+#[derive(Serialize)]
+struct StoreResult {
+    /// Path to the archive
+    path: String,
+}
+
+/// Result of the "agama logs list" subcommand, as reported in JSON mode
+#[derive(Serialize)]
+struct ListResult {
+    paths: Vec<String>,
+    commands: Vec<String>,
+}
+
 /// Main entry point called from agama CLI main loop
-pub async fn run(subcommand: LogsCommands) -> anyhow::Result<()> {
+pub async fn run(subcommand: LogsCommands, format: OutputFormat) -> anyhow::Result<()> {
     match subcommand {
         LogsCommands::Store {
             verbose,
@@ -39,17 +55,28 @@ pub async fn run(subcommand: LogsCommands) -> anyhow::Result<()> {
             // for now we always use / add defaults if any
             let destination = parse_destination(destination)?;
             let options = LogOptions {
-                verbose,
+                // in JSON mode, the standard output is reserved for the result
+                verbose: verbose && !format.is_json(),
+                quiet: format.is_json(),
                 destination,
                 ..Default::default()
             };
 
-            Ok(store(options)?)
+            let path = store(options)?;
+            format.print(&StoreResult { path }, || {})
         }
         LogsCommands::List => {
-            list(LogOptions::default());
-
-            Ok(())
+            let options = LogOptions::default();
+            if !format.is_json() {
+                list(options);
+                return Ok(());
+            }
+
+            let result = ListResult {
+                paths: options.paths,
+                commands: options.commands.into_iter().map(|c| c.0).collect(),
+            };
+            format.print(&result, || {})
         }
     }
 }
@@ -142,6 +169,8 @@ struct LogOptions {
     paths: Vec<String>,
     commands: Vec<(String, String)>,
     verbose: bool,
+    // do not write anything to the standard output
+    quiet: bool,
     destination: PathBuf,
 }
 
@@ -154,6 +183,7 @@ impl Default for LogOptions {
                 .map(|(cmd, name)| (cmd.to_string(), name.to_string()))
                 .collect(),
             verbose: false,
+            quiet: false,
             destination: PathBuf::from(DEFAULT_RESULT),
         }
     }
@@ -365,7 +395,9 @@ fn set_archive_permissions(archive: &String) -> io::Result<()> {
 }
 
 /// Handler for the "agama logs store" subcommand
-fn store(options: LogOptions) -> Result<(), io::Error> {
+///
+/// It returns the path to the archive.
+fn store(options: LogOptions) -> Result<String, io::Error> {
     if !Uid::effective().is_root() {
         panic!("No Root, no logs. Sorry.");
     }
@@ -398,7 +430,7 @@ fn store(options: LogOptions) -> Result<(), io::Error> {
     if verbose {
         showln(true, format!("Storing result in: \"{}\"", result).as_str());
     } else {
-        showln(true, result.as_str());
+        showln(!options.quiet, result.as_str());
     }
 
     for log in log_sources.iter() {
@@ -420,7 +452,8 @@ fn store(options: LogOptions) -> Result<(), io::Error> {
         showln(verbose, res.to_string().as_str());
     }
 
-    compress_logs(&tmp_dir, &result)
+    compress_logs(&tmp_dir, &result)?;
+    Ok(result)
 }
 
 /// Handler for the "agama logs list" subcommand
@@ -4,27 +4,36 @@ mod auth;
 mod commands;
 mod config;
 mod error;
+mod events;
+mod issues;
 mod logs;
+mod output;
 mod profile;
 mod progress;
 mod questions;
 
 use crate::error::CliError;
+use agama_lib::base_http_client::BaseHTTPClient;
 use agama_lib::error::ServiceError;
-use agama_lib::manager::ManagerClient;
-use agama_lib::progress::ProgressMonitor;
+use agama_lib::manager::ManagerHTTPClient;
+use agama_lib::progress::WebSocketProgressMonitor;
 use auth::run as run_auth_cmd;
 use commands::Commands;
 use config::run as run_config_cmd;
+use events::run as run_events_cmd;
+use issues::run as run_issues_cmd;
 use logs::run as run_logs_cmd;
+use output::OutputFormat;
 use profile::run as run_profile_cmd;
-use progress::InstallerProgress;
+use progress::{InstallerProgress, JsonProgress};
 use questions::run as run_questions_cmd;
 use std::{
+    path::PathBuf,
     process::{ExitCode, Termination},
     thread::sleep,
     time::Duration,
 };
+use url::Url;
 
 /// Agama's command-line interface
 ///
@@ -35,38 +44,129 @@ use std::{
 #[derive(Parser)]
 #[command(name = "agama", about, long_about, max_term_width = 100)]
 struct Cli {
+    /// Agama server to connect to.
+    ///
+    /// It can be a host name or an IP address (e.g., "agama.example.net"), which is reached
+    /// through HTTPS, or the full URL of the API (e.g., "http://localhost/api").
+    #[arg(
+        long,
+        global = true,
+        visible_alias = "api",
+        env = "AGAMA_HOST",
+        default_value = "http://localhost/api"
+    )]
+    pub host: String,
+
+    /// Do not verify the server's TLS certificate.
+    ///
+    /// It is meant to connect to an installer using the self-signed certificate generated by
+    /// agama-web-server. Consider using the "--certificate" option instead.
+    #[arg(long, global = true)]
+    pub insecure: bool,
+
+    /// Path to a PEM certificate to verify the server's TLS certificate.
+    ///
+    /// You can use the certificate written by agama-web-server to /etc/agama.d/ssl/cert.pem.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub certificate: Option<PathBuf>,
+
+    /// Output format.
+    ///
+    /// The JSON format writes a single document with the result (or the error) of the command,
+    /// including a stable error code. It is meant to be consumed by other programs.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
-async fn probe() -> anyhow::Result<()> {
-    let another_manager = build_manager().await?;
-    let probe = tokio::spawn(async move {
-        let _ = another_manager.probe().await;
-    });
-    show_progress().await?;
+/// Builds the URL of Agama's HTTP API.
+///
+/// A plain host name uses HTTPS and the default "/api" path.
+///
+/// * `host`: host name, IP address or URL.
+fn api_url(host: &str) -> anyhow::Result<Url> {
+    let mut url = if host.contains("://") {
+        Url::parse(host)?
+    } else {
+        Url::parse(&format!("https://{host}"))?
+    };
+
+    if url.path().is_empty() || url.path() == "/" {
+        url.set_path("/api");
+    }
 
-    Ok(probe.await?)
+    Ok(url)
+}
+
+/// Builds the (not authenticated) HTTP client to talk to the server selected in the command-line.
+///
+/// * `cli`: command-line arguments.
+fn build_client(cli: &Cli) -> anyhow::Result<BaseHTTPClient> {
+    let url = api_url(&cli.host)?;
+    let mut client = BaseHTTPClient::default().with_base_url(url.as_str());
+
+    if cli.insecure {
+        client = client.insecure()?;
+    }
+
+    if let Some(path) = &cli.certificate {
+        let pem = std::fs::read(path)?;
+        client = client.with_certificate(&pem)?;
+    }
+
+    Ok(client)
+}
+
+async fn probe(client: BaseHTTPClient, format: OutputFormat) -> anyhow::Result<()> {
+    let manager = ManagerHTTPClient::new_with_base(client.clone());
+    manager.wait().await?;
+    let monitor = WebSocketProgressMonitor::connect(client).await?;
+    manager.probe().await?;
+    report_progress(monitor, format).await?;
+    format.print(&(), || {})
 }
 
 /// Starts the installation process
 ///
-/// Before starting, it makes sure that the manager is idle.
+/// Before starting, it makes sure that the manager is idle. If the installation cannot start, the
+/// error includes the reasons (issues and busy services).
 ///
-/// * `manager`: the manager client.
-async fn install(manager: &ManagerClient<'_>, max_attempts: u8) -> anyhow::Result<()> {
-    if manager.is_busy().await {
-        println!("Agama's manager is busy. Waiting until it is ready...");
+/// * `client`: authenticated HTTP client.
+/// * `max_attempts`: number of attempts to start the installation.
+/// * `wait`: whether to wait until the installation finishes.
+/// * `format`: output format.
+async fn install(
+    client: BaseHTTPClient,
+    max_attempts: u8,
+    wait: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let manager = ManagerHTTPClient::new_with_base(client.clone());
+    if manager.is_busy().await? {
+        eprintln!("Agama's manager is busy. Waiting until it is ready...");
     }
 
     // Make sure that the manager is ready
     manager.wait().await?;
 
-    if !manager.can_install().await? {
-        return Err(CliError::Validation)?;
+    let status = manager.status().await?;
+    if !status.can_install {
+        // older servers do not report the blockers
+        if status.blockers.is_empty() {
+            return Err(CliError::Validation)?;
+        }
+        return Err(CliError::InstallationBlocked(status.blockers))?;
     }
 
-    let progress = tokio::spawn(async { show_progress().await });
+    // connect before starting the installation to not miss any event
+    let monitor = if wait {
+        Some(WebSocketProgressMonitor::connect(client).await?)
+    } else {
+        None
+    };
+
     // Try to start the installation up to max_attempts times.
     let mut attempts = 1;
     loop {
@@ -86,59 +186,94 @@ async fn install(manager: &ManagerClient<'_>, max_attempts: u8) -> anyhow::Resul
         attempts += 1;
         sleep(Duration::from_secs(1));
     }
-    let _ = progress.await;
-    Ok(())
+    if let Some(monitor) = monitor {
+        report_progress(monitor, format).await?;
+    }
+    format.print(&(), || {})
 }
 
-async fn show_progress() -> Result<(), ServiceError> {
-    // wait 1 second to give other task chance to start, so progress can display something
-    tokio::time::sleep(Duration::from_secs(1)).await;
-    let conn = agama_lib::connection().await?;
-    let mut monitor = ProgressMonitor::new(conn).await.unwrap();
-    let presenter = InstallerProgress::new();
-    monitor
-        .run(presenter)
-        .await
-        .expect("failed to monitor the progress");
-    Ok(())
+/// Reports the progress of the current operation, if any, until the manager is idle.
+///
+/// * `client`: authenticated HTTP client.
+/// * `format`: output format.
+async fn show_progress(client: BaseHTTPClient, format: OutputFormat) -> Result<(), ServiceError> {
+    let monitor = WebSocketProgressMonitor::connect(client).await?;
+    if format.is_json() {
+        monitor.wait(JsonProgress).await
+    } else {
+        monitor.wait(InstallerProgress::new()).await
+    }
 }
 
-async fn wait_for_services(manager: &ManagerClient<'_>) -> Result<(), ServiceError> {
-    let services = manager.busy_services().await?;
+/// Reports the progress of the operation just started through an already connected monitor.
+///
+/// * `monitor`: progress monitor.
+/// * `format`: output format.
+async fn report_progress(
+    monitor: WebSocketProgressMonitor,
+    format: OutputFormat,
+) -> Result<(), ServiceError> {
+    if format.is_json() {
+        monitor.run(JsonProgress).await
+    } else {
+        monitor.run(InstallerProgress::new()).await
+    }
+}
+
+async fn wait_for_services(
+    client: &BaseHTTPClient,
+    format: OutputFormat,
+) -> Result<(), ServiceError> {
+    let manager = ManagerHTTPClient::new_with_base(client.clone());
     // TODO: having it optional
-    if !services.is_empty() {
+    if manager.is_busy().await? {
         eprintln!("The Agama service is busy. Waiting for it to be available...");
-        show_progress().await?
+        show_progress(client.clone(), format).await?
     }
     Ok(())
 }
 
-async fn build_manager<'a>() -> anyhow::Result<ManagerClient<'a>> {
-    let conn = agama_lib::connection().await?;
-    Ok(ManagerClient::new(conn).await?)
+/// Downloads a file and writes it to the standard output.
+///
+/// In JSON mode, the content is included in the result.
+///
+/// * `url`: URL of the file.
+/// * `format`: output format.
+fn download(url: &str, format: OutputFormat) -> anyhow::Result<()> {
+    if !format.is_json() {
+        return crate::profile::download(url, std::io::stdout());
+    }
+
+    let mut content = vec![];
+    crate::profile::download(url, &mut content)?;
+    format.print(&String::from_utf8_lossy(&content), || {})
 }
 
 async fn run_command(cli: Cli) -> Result<(), ServiceError> {
+    let client = build_client(&cli)?;
+    let format = cli.output;
+
     match cli.command {
         Commands::Config(subcommand) => {
-            let manager = build_manager().await?;
-            wait_for_services(&manager).await?;
-            run_config_cmd(subcommand).await?
+            let client = client.authenticated()?;
+            wait_for_services(&client, format).await?;
+            run_config_cmd(subcommand, client, format).await?
         }
         Commands::Probe => {
-            let manager = build_manager().await?;
-            wait_for_services(&manager).await?;
-            probe().await?
+            let client = client.authenticated()?;
+            wait_for_services(&client, format).await?;
+            probe(client, format).await?
         }
-        Commands::Profile(subcommand) => run_profile_cmd(subcommand).await?,
-        Commands::Install => {
-            let manager = build_manager().await?;
-            install(&manager, 3).await?
+        Commands::Issues => run_issues_cmd(client.authenticated()?, format).await?,
+        Commands::Profile(subcommand) => run_profile_cmd(subcommand, client, format).await?,
+        Commands::Install { no_wait, .. } => {
+            install(client.authenticated()?, 3, !no_wait, format).await?
         }
-        Commands::Questions(subcommand) => run_questions_cmd(subcommand).await?,
-        Commands::Logs(subcommand) => run_logs_cmd(subcommand).await?,
-        Commands::Auth(subcommand) => run_auth_cmd(subcommand).await?,
-        Commands::Download { url } => crate::profile::download(&url, std::io::stdout())?,
+        Commands::Questions(subcommand) => run_questions_cmd(subcommand, client, format).await?,
+        Commands::Logs(subcommand) => run_logs_cmd(subcommand, format).await?,
+        Commands::Auth(subcommand) => run_auth_cmd(subcommand, client, format).await?,
+        Commands::Events(args) => run_events_cmd(args, client.authenticated()?, format).await?,
+        Commands::Download { url } => download(&url, format)?,
     };
 
     Ok(())
@@ -161,9 +296,10 @@ impl Termination for CliResult {
 #[tokio::main]
 async fn main() -> CliResult {
     let cli = Cli::parse();
+    let format = cli.output;
 
     if let Err(error) = run_command(cli).await {
-        eprintln!("{:?}", error);
+        format.print_error(&error);
         return CliResult::Error;
     }
     CliResult::Ok
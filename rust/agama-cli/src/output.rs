@@ -0,0 +1,209 @@
+//! Implements the output of the commands, which can be meant for humans or for other programs.
+//!
+//! In JSON mode, each command writes a single JSON document to the standard output:
+//!
+//! * `{"success": true, "result": ...}` when it succeeds.
+//! * `{"success": false, "error": {"code": ..., "message": ..., "details": ...}}` when it fails.
+//!
+//! The error codes are stable, so programs can rely on them (see [ServiceError::code]). As an
+//! exception, `agama events` writes one event per line (NDJSON).
+
+use crate::error::CliError;
+use agama_lib::error::{ProfileError, ServiceError};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Output format of the commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON documents.
+    Json,
+}
+
+#[derive(Serialize)]
+struct Success<'a, T: Serialize> {
+    success: bool,
+    result: &'a T,
+}
+
+#[derive(Serialize)]
+struct Failure {
+    success: bool,
+    error: ErrorDetails,
+}
+
+#[derive(Serialize)]
+struct ErrorDetails {
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+}
+
+impl OutputFormat {
+    /// Whether the output is meant for other programs.
+    pub fn is_json(&self) -> bool {
+        *self == Self::Json
+    }
+
+    /// Writes the result of a command.
+    ///
+    /// * `result`: result to write in JSON mode.
+    /// * `text`: function to write the result in text mode.
+    pub fn print<T: Serialize>(&self, result: &T, text: impl FnOnce()) -> anyhow::Result<()> {
+        match self {
+            Self::Text => text(),
+            Self::Json => {
+                let success = Success {
+                    success: true,
+                    result,
+                };
+                println!("{}", serde_json::to_string_pretty(&success)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a command error.
+    ///
+    /// In text mode, it goes to the standard error.
+    ///
+    /// * `error`: error to write.
+    pub fn print_error(&self, error: &ServiceError) {
+        match self {
+            Self::Text => eprintln!("{:?}", error),
+            Self::Json => {
+                let failure = Failure {
+                    success: false,
+                    error: error_details(error),
+                };
+                match serde_json::to_string_pretty(&failure) {
+                    Ok(json) => println!("{}", json),
+                    Err(_) => eprintln!("{:?}", error),
+                }
+            }
+        }
+    }
+}
+
+/// Builds the error details, looking for the original error when it is wrapped.
+///
+/// * `error`: error to describe.
+fn error_details(error: &ServiceError) -> ErrorDetails {
+    let ServiceError::Anyhow(inner) = error else {
+        return ErrorDetails {
+            code: error.code(),
+            message: error.to_string(),
+            details: None,
+        };
+    };
+
+    let message = format!("{:#}", inner);
+    if let Some(cli_error) = inner.downcast_ref::<CliError>() {
+        ErrorDetails {
+            code: cli_error.code(),
+            message,
+            details: cli_error.details(),
+        }
+    } else if let Some(service_error) = inner.downcast_ref::<ServiceError>() {
+        error_details(service_error)
+    } else if let Some(profile_error) = inner.downcast_ref::<ProfileError>() {
+        ErrorDetails {
+            code: profile_error.code(),
+            message,
+            details: None,
+        }
+    } else {
+        ErrorDetails {
+            code: error.code(),
+            message,
+            details: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::error_details;
+    use crate::error::CliError;
+    use agama_lib::{
+        error::{ProfileError, ServiceError},
+        install_settings::SettingsDiff,
+        manager::InstallationBlockers,
+    };
+    use anyhow::Context;
+    use serde_json::json;
+
+    #[test]
+    fn test_service_error_details() {
+        let error = ServiceError::UnknownSection("foo".to_string());
+        let details = error_details(&error);
+        assert_eq!(details.code, "unknown_section");
+        assert_eq!(details.message, "Unknown settings section 'foo'");
+        assert!(details.details.is_none());
+
+        // wrapped by the anyhow context
+        let error: ServiceError = Err::<(), _>(ServiceError::NotAuthenticated)
+            .context("Could not load the settings")
+            .unwrap_err()
+            .into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "not_authenticated");
+    }
+
+    #[test]
+    fn test_profile_error_details() {
+        let error = ProfileError::EvaluationError("syntax error".to_string());
+        let error: ServiceError = anyhow::Error::from(error)
+            .context("Could not evaluate the profile")
+            .into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "profile_evaluation");
+        assert!(details
+            .message
+            .starts_with("Could not evaluate the profile: "));
+        assert!(details.details.is_none());
+    }
+
+    #[test]
+    fn test_cli_error_details() {
+        let error: ServiceError = anyhow::Error::from(CliError::Validation).into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "invalid_settings");
+        assert!(details.details.is_none());
+
+        let blockers = InstallationBlockers {
+            busy_services: vec!["org.opensuse.Agama.Software1".to_string()],
+            ..Default::default()
+        };
+        let error: ServiceError = anyhow::Error::from(CliError::InstallationBlocked(blockers))
+            .context("Could not install the system")
+            .into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "installation_blocked");
+        assert_eq!(
+            details.details,
+            Some(json!({
+                "busyServices": ["org.opensuse.Agama.Software1"],
+                "issues": {}
+            }))
+        );
+
+        let error: ServiceError =
+            anyhow::Error::from(CliError::SettingsDiffer(SettingsDiff::default())).into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "settings_differ");
+        assert_eq!(details.details, Some(json!({"changes": []})));
+    }
+
+    #[test]
+    fn test_generic_error_details() {
+        let error: ServiceError = anyhow::anyhow!("Something went wrong").into();
+        let details = error_details(&error);
+        assert_eq!(details.code, "generic");
+        assert_eq!(details.message, "Something went wrong");
+    }
+}
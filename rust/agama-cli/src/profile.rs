@@ -1,13 +1,13 @@
+use crate::output::OutputFormat;
 use agama_lib::{
-    auth::AuthToken,
-    connection,
-    install_settings::InstallSettings,
+    base_http_client::BaseHTTPClient,
+    install_settings::{InstallSettings, InstallSettingsHTTPClient},
     profile::{AutoyastProfile, ProfileEvaluator, ProfileValidator, ValidationResult},
-    Store as SettingsStore,
 };
 use anyhow::Context;
 use clap::Subcommand;
 use curl::easy::Easy;
+use serde::Serialize;
 use std::os::unix::process::CommandExt;
 use std::{
     fs::File,
@@ -72,12 +72,42 @@ pub fn download(url: &str, mut out_fd: impl Write) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn validate(path: &PathBuf) -> anyhow::Result<()> {
+/// Result of validating a profile, as reported in JSON mode.
+#[derive(Serialize)]
+struct Validation {
+    valid: bool,
+    errors: Vec<String>,
+}
+
+fn validate_file(path: &PathBuf) -> anyhow::Result<ValidationResult> {
     let validator = ProfileValidator::default_schema()?;
     // let path = Path::new(&path);
-    let result = validator
+    validator
         .validate_file(path)
-        .context(format!("Could not validate the profile {:?}", path))?;
+        .context(format!("Could not validate the profile {:?}", path))
+}
+
+impl From<&ValidationResult> for Validation {
+    fn from(result: &ValidationResult) -> Self {
+        match result {
+            ValidationResult::Valid => Self {
+                valid: true,
+                errors: vec![],
+            },
+            ValidationResult::NotValid(errors) => Self {
+                valid: false,
+                errors: errors.clone(),
+            },
+        }
+    }
+}
+
+fn validate(path: &PathBuf, format: OutputFormat) -> anyhow::Result<()> {
+    let result = validate_file(path)?;
+    format.print(&Validation::from(&result), || print_validation(result))
+}
+
+fn print_validation(result: ValidationResult) {
     match result {
         ValidationResult::Valid => {
             println!("The profile is valid")
@@ -89,18 +119,31 @@ fn validate(path: &PathBuf) -> anyhow::Result<()> {
             }
         }
     }
-    Ok(())
 }
 
-fn evaluate(path: &PathBuf) -> anyhow::Result<()> {
+fn evaluate(path: &Path, format: OutputFormat) -> anyhow::Result<()> {
     let evaluator = ProfileEvaluator {};
+    if !format.is_json() {
+        evaluator
+            .evaluate(path, stdout())
+            .context("Could not evaluate the profile".to_string())?;
+        return Ok(());
+    }
+
+    let mut output = vec![];
     evaluator
-        .evaluate(&path, stdout())
+        .evaluate(path, &mut output)
         .context("Could not evaluate the profile".to_string())?;
-    Ok(())
+    let profile: serde_json::Value = serde_json::from_slice(&output)?;
+    format.print(&profile, || {})
 }
 
-async fn import(url_string: String, dir: Option<PathBuf>) -> anyhow::Result<()> {
+async fn import(
+    client: BaseHTTPClient,
+    url_string: String,
+    dir: Option<PathBuf>,
+    format: OutputFormat,
+) -> anyhow::Result<Validation> {
     let url = Url::parse(&url_string)?;
     let tmpdir = TempDir::new()?; // TODO: create it only if dir is not passed
     let path = url.path();
@@ -140,33 +183,50 @@ async fn import(url_string: String, dir: Option<PathBuf>) -> anyhow::Result<()>
         output_path = output_dir.join("profile.json");
     }
 
-    validate(&output_path)?;
-    store_settings(&output_path).await?;
+    // the validation errors are reported, but they do not stop the import
+    let result = validate_file(&output_path)?;
+    let validation = Validation::from(&result);
+    if !format.is_json() {
+        print_validation(result);
+    }
+    store_settings(client, &output_path).await?;
 
-    Ok(())
+    Ok(validation)
 }
 
-async fn store_settings<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
-    let token = AuthToken::find().context("You are not logged in")?;
-    let client = agama_lib::http_client(token.as_str())?;
-    let store = SettingsStore::new(connection().await?, client).await?;
+async fn store_settings<P: AsRef<Path>>(client: BaseHTTPClient, path: P) -> anyhow::Result<()> {
+    let settings_client = InstallSettingsHTTPClient::new_with_base(client.authenticated()?);
     let settings = InstallSettings::from_file(&path)?;
-    store.store(&settings).await?;
+    settings_client.set_config(&settings).await?;
     Ok(())
 }
 
-fn autoyast(url_string: String) -> anyhow::Result<()> {
+fn autoyast(url_string: String, format: OutputFormat) -> anyhow::Result<()> {
     let url = Url::parse(&url_string)?;
     let reader = AutoyastProfile::new(&url)?;
-    reader.read_into(std::io::stdout())?;
-    Ok(())
+    if !format.is_json() {
+        reader.read_into(std::io::stdout())?;
+        return Ok(());
+    }
+
+    let mut output = vec![];
+    reader.read_into(&mut output)?;
+    let profile: serde_json::Value = serde_json::from_slice(&output)?;
+    format.print(&profile, || {})
 }
 
-pub async fn run(subcommand: ProfileCommands) -> anyhow::Result<()> {
+pub async fn run(
+    subcommand: ProfileCommands,
+    client: BaseHTTPClient,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
     match subcommand {
-        ProfileCommands::Autoyast { url } => autoyast(url),
-        ProfileCommands::Validate { path } => validate(&path),
-        ProfileCommands::Evaluate { path } => evaluate(&path),
-        ProfileCommands::Import { url, dir } => import(url, dir).await,
+        ProfileCommands::Autoyast { url } => autoyast(url, format),
+        ProfileCommands::Validate { path } => validate(&path, format),
+        ProfileCommands::Evaluate { path } => evaluate(&path, format),
+        ProfileCommands::Import { url, dir } => {
+            let validation = import(client, url, dir, format).await?;
+            format.print(&validation, || {})
+        }
     }
 }
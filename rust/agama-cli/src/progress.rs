@@ -61,3 +61,33 @@ impl ProgressPresenter for InstallerProgress {
         }
     }
 }
+
+/// Reports the installer progress as JSON lines through the standard error
+///
+/// It is meant for the JSON output mode, where the standard output is reserved for the result.
+pub struct JsonProgress;
+
+impl JsonProgress {
+    fn write(&self, progress: &Progress) {
+        if let Ok(json) = serde_json::to_string(progress) {
+            eprintln!("{}", json);
+        }
+    }
+}
+
+#[async_trait]
+impl ProgressPresenter for JsonProgress {
+    async fn start(&mut self, progress: &Progress) {
+        if !progress.finished {
+            self.write(progress);
+        }
+    }
+
+    async fn update_main(&mut self, progress: &Progress) {
+        self.write(progress);
+    }
+
+    async fn update_detail(&mut self, _progress: &Progress) {}
+
+    async fn finish(&mut self) {}
+}
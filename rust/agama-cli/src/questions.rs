@@ -1,8 +1,11 @@
-use agama_lib::proxies::Questions1Proxy;
+use agama_lib::base_http_client::BaseHTTPClient;
+use agama_lib::error::ServiceError;
 use agama_lib::questions::http_client::HTTPClient;
-use agama_lib::{connection, error::ServiceError};
+use anyhow::Context;
 use clap::{Args, Subcommand, ValueEnum};
 
+use crate::output::OutputFormat;
+
 // TODO: use for answers also JSON to be consistent
 #[derive(Subcommand, Debug)]
 pub enum QuestionsCommands {
@@ -17,7 +20,7 @@ pub enum QuestionsCommands {
     /// Please check Agama documentation for more details and examples:
     /// <https://github.com/openSUSE/agama/blob/master/doc/questions.md>
     Answers {
-        /// Path to a file containing the answers in YAML format.
+        /// Path to a local file containing the answers in YAML format.
         path: String,
     },
     /// Prints the list of questions that are waiting for an answer in JSON format
@@ -40,33 +43,29 @@ pub enum Modes {
     NonInteractive,
 }
 
-async fn set_mode(proxy: Questions1Proxy<'_>, value: Modes) -> Result<(), ServiceError> {
-    proxy
-        .set_interactive(value == Modes::Interactive)
-        .await
-        .map_err(|e| e.into())
+async fn set_mode(client: HTTPClient, value: Modes) -> Result<(), ServiceError> {
+    client.set_mode(value == Modes::Interactive).await
 }
 
-async fn set_answers(proxy: Questions1Proxy<'_>, path: String) -> Result<(), ServiceError> {
-    proxy
-        .add_answer_file(path.as_str())
-        .await
-        .map_err(|e| e.into())
+/// Reads the answers from a local file and sends them to the server.
+async fn set_answers(client: HTTPClient, path: String) -> Result<(), ServiceError> {
+    let file =
+        std::fs::File::open(&path).context(format!("Could not read the answers {:?}", path))?;
+    let answers: serde_json::Value =
+        serde_yaml::from_reader(file).context(format!("Could not parse the answers {:?}", path))?;
+    client.add_answers(&answers).await
 }
 
-async fn list_questions() -> Result<(), ServiceError> {
-    let client = HTTPClient::new()?;
+async fn list_questions(client: HTTPClient, format: OutputFormat) -> Result<(), ServiceError> {
     let questions = client.list_questions().await?;
     // FIXME: if performance is bad, we can skip converting json from http to struct and then
     // serialize it, but it won't be pretty string
     let questions_json = serde_json::to_string_pretty(&questions)
         .map_err(|e| ServiceError::InternalError(e.to_string()))?;
-    println!("{}", questions_json);
-    Ok(())
+    Ok(format.print(&questions, || println!("{}", questions_json))?)
 }
 
-async fn ask_question() -> Result<(), ServiceError> {
-    let client = HTTPClient::new()?;
+async fn ask_question(client: HTTPClient, format: OutputFormat) -> Result<(), ServiceError> {
     let question = serde_json::from_reader(std::io::stdin())?;
 
     let created_question = client.create_question(&question).await?;
@@ -78,20 +77,28 @@ async fn ask_question() -> Result<(), ServiceError> {
     let answer = client.get_answer(id).await?;
     let answer_json = serde_json::to_string_pretty(&answer)
         .map_err(|e| ServiceError::InternalError(e.to_string()))?;
-    println!("{}", answer_json);
+    format.print(&answer, || println!("{}", answer_json))?;
 
     client.delete_question(id).await?;
     Ok(())
 }
 
-pub async fn run(subcommand: QuestionsCommands) -> Result<(), ServiceError> {
-    let connection = connection().await?;
-    let proxy = Questions1Proxy::new(&connection).await?;
-
+pub async fn run(
+    subcommand: QuestionsCommands,
+    client: BaseHTTPClient,
+    format: OutputFormat,
+) -> Result<(), ServiceError> {
+    let client = HTTPClient::new_with_base(client.authenticated()?);
     match subcommand {
-        QuestionsCommands::Mode(value) => set_mode(proxy, value.value).await,
-        QuestionsCommands::Answers { path } => set_answers(proxy, path).await,
-        QuestionsCommands::List => list_questions().await,
-        QuestionsCommands::Ask => ask_question().await,
+        QuestionsCommands::Mode(value) => {
+            set_mode(client, value.value).await?;
+            Ok(format.print(&(), || {})?)
+        }
+        QuestionsCommands::Answers { path } => {
+            set_answers(client, path).await?;
+            Ok(format.print(&(), || {})?)
+        }
+        QuestionsCommands::List => list_questions(client, format).await,
+        QuestionsCommands::Ask => ask_question(client, format).await,
     }
 }
@@ -9,22 +9,24 @@
 //! JWT) in `/run/agama/token`. That file is only readable by the root user and
 //! can be used by any Agama component.
 //!
-//! ## The user token
+//! ## The user tokens
 //!
 //! When a user does not have access to the master token it needs to authenticate
 //! with the server. In that process, it obtains a new token that should be stored
-//! in user's home directory (`$HOME/.local/agama/token`).
+//! in user's home directory (`$HOME/.local/agama/tokens.json`).
 //!
-//! ## A simplistic API
+//! That file keeps a token for each server, indexed by the URL of its API (e.g.,
+//! `https://agama.example.net/api`). See [UserTokens] for further details.
 //!
-//! The current API is rather limited and it does not support, for instance,
-//! keeping tokens for different servers. We might extend this API if needed
-//! in the future.
+//! For backward compatibility, the token in `$HOME/.local/agama/token` is still
+//! used when talking to the local server.
 
 const USER_TOKEN_PATH: &str = ".local/agama/token";
+const USER_TOKENS_PATH: &str = ".local/agama/tokens.json";
 const AGAMA_TOKEN_FILE: &str = "/run/agama/token";
 
 use std::{
+    collections::BTreeMap,
     fmt::Display,
     fs::{self, File},
     io::{self, BufRead, BufReader, Write},
@@ -36,12 +38,14 @@ use chrono::{Duration, Utc};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use url::Url;
 
 #[derive(Error, Debug)]
 #[error("Invalid authentication token: {0}")]
 pub struct AuthTokenError(#[from] jsonwebtoken::errors::Error);
 
 /// Represents an authentication token (JWT).
+#[derive(Clone)]
 pub struct AuthToken(String);
 
 impl AuthToken {
@@ -54,12 +58,24 @@ impl AuthToken {
 
     /// Generates a new token using the given secret.
     ///
+    /// The token expires after one day.
+    ///
     /// * `secret`: secret to encode the token.
     pub fn generate(secret: &str) -> Result<Self, AuthTokenError> {
-        let claims = TokenClaims::default();
+        Self::generate_with_claims(secret, &TokenClaims::default())
+    }
+
+    /// Generates a new token including the given claims.
+    ///
+    /// * `secret`: secret to encode the token.
+    /// * `claims`: claims to include in the token.
+    pub fn generate_with_claims(
+        secret: &str,
+        claims: &TokenClaims,
+    ) -> Result<Self, AuthTokenError> {
         let token = jsonwebtoken::encode(
             &Header::default(),
-            &claims,
+            claims,
             &EncodingKey::from_secret(secret.as_ref()),
         )?;
         Ok(AuthToken(token))
@@ -79,6 +95,24 @@ impl AuthToken {
         Self::read(AGAMA_TOKEN_FILE).ok()
     }
 
+    /// Finds an usable token for the current user and the given server.
+    ///
+    /// It searches for a token for the server in the user's tokens. For the local
+    /// server, it falls back to the tokens found by [AuthToken::find].
+    ///
+    /// * `server`: URL of the server's API (e.g., `https://agama.example.net/api`).
+    pub fn find_for(server: &str) -> Option<Self> {
+        if let Some(token) = UserTokens::load().ok().and_then(|t| t.get(server)) {
+            return Some(token);
+        }
+
+        if is_local(server) {
+            return Self::find();
+        }
+
+        None
+    }
+
     /// Reads the token from the given path.
     ///
     /// * `path`: file's path to read the token from.
@@ -118,6 +152,32 @@ impl AuthToken {
         Ok(())
     }
 
+    /// Writes the token for the given server to the user's tokens.
+    ///
+    /// * `server`: URL of the server's API.
+    pub fn write_user_token_for(&self, server: &str) -> io::Result<()> {
+        let mut tokens = UserTokens::load()?;
+        tokens.insert(server, self);
+        tokens.save()
+    }
+
+    /// Removes the user token for the given server.
+    ///
+    /// In the case of the local server, it removes the token from `$HOME/.local/agama/token` too.
+    ///
+    /// * `server`: URL of the server's API.
+    pub fn remove_user_token_for(server: &str) -> io::Result<()> {
+        let mut tokens = UserTokens::load()?;
+        if tokens.remove(server) {
+            tokens.save()?;
+        }
+
+        if is_local(server) {
+            Self::remove_user_token()?;
+        }
+        Ok(())
+    }
+
     /// Returns the claims from the token.
     ///
     /// * `secret`: secret to decode the token.
@@ -147,14 +207,7 @@ impl AuthToken {
     }
 
     fn user_token_path() -> io::Result<PathBuf> {
-        let Some(path) = home::home_dir() else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Cannot find the user's home directory",
-            ));
-        };
-
-        Ok(path.join(USER_TOKEN_PATH))
+        Ok(home_dir()?.join(USER_TOKEN_PATH))
     }
 }
 
@@ -164,33 +217,212 @@ impl Display for AuthToken {
     }
 }
 
+/// User tokens for the different servers.
+///
+/// The tokens are indexed by the URL of the server's API and they are stored in a JSON file
+/// which is only readable by the user.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UserTokens(BTreeMap<String, String>);
+
+impl UserTokens {
+    /// Loads the tokens from the user's home directory.
+    ///
+    /// If the file does not exist, it returns an empty set of tokens.
+    pub fn load() -> io::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(path)
+    }
+
+    /// Writes the tokens to the user's home directory.
+    pub fn save(&self) -> io::Result<()> {
+        self.write(Self::path()?)
+    }
+
+    /// Reads the tokens from the given path.
+    ///
+    /// * `path`: file's path to read the tokens from.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let tokens = serde_json::from_reader(BufReader::new(file))?;
+        Ok(tokens)
+    }
+
+    /// Writes the tokens to the given path.
+    ///
+    /// It takes care of setting the right permissions (0600).
+    ///
+    /// * `path`: file's path to write the tokens to.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .mode(0o600)
+            .open(path)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        Ok(())
+    }
+
+    /// Returns the token for the given server, if any.
+    ///
+    /// * `server`: URL of the server's API.
+    pub fn get(&self, server: &str) -> Option<AuthToken> {
+        self.0
+            .get(&normalize(server))
+            .map(|t| AuthToken::new(t.as_str()))
+    }
+
+    /// Sets the token for the given server, replacing the previous one.
+    ///
+    /// * `server`: URL of the server's API.
+    /// * `token`: token to store.
+    pub fn insert(&mut self, server: &str, token: &AuthToken) {
+        self.0.insert(normalize(server), token.to_string());
+    }
+
+    /// Removes the token for the given server.
+    ///
+    /// Returns whether there was a token for the server.
+    ///
+    /// * `server`: URL of the server's API.
+    pub fn remove(&mut self, server: &str) -> bool {
+        self.0.remove(&normalize(server)).is_some()
+    }
+
+    /// Returns the URLs of the servers with a token.
+    pub fn servers(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    fn path() -> io::Result<PathBuf> {
+        Ok(home_dir()?.join(USER_TOKENS_PATH))
+    }
+}
+
+/// Normalizes the server URL so it can be used as a key.
+fn normalize(server: &str) -> String {
+    server.trim_end_matches('/').to_string()
+}
+
+/// Determines whether the given URL points to the local server.
+fn is_local(server: &str) -> bool {
+    let Ok(url) = Url::parse(server) else {
+        return false;
+    };
+
+    matches!(
+        url.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    )
+}
+
+fn home_dir() -> io::Result<PathBuf> {
+    home::home_dir().ok_or_else(|| io::Error::other("Cannot find the user's home directory"))
+}
+
 /// Claims that are included in the token.
 ///
 /// See <https://datatracker.ietf.org/doc/html/rfc7519> for reference.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
+    /// Expiration time (as a UNIX timestamp).
     pub exp: i64,
+    /// Issued at (as a UNIX timestamp).
+    #[serde(default)]
+    pub iat: i64,
+    /// Unique identifier of the token. It allows revoking the token.
+    ///
+    /// It is required, so tokens issued before it was introduced (which could not be revoked)
+    /// are rejected.
+    pub jti: String,
+    /// Scope of the token. A token without a scope is not restricted at all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<TokenScope>,
 }
 
-impl Default for TokenClaims {
-    fn default() -> Self {
-        let mut exp = Utc::now();
+impl TokenClaims {
+    /// Builds the claims for a new token which is valid for the given amount of time.
+    ///
+    /// * `lifetime`: how long the token is valid.
+    pub fn new(lifetime: Duration) -> Self {
+        let iat = Utc::now();
+        let exp = iat + lifetime;
 
-        if let Some(days) = Duration::try_days(1) {
-            exp += days;
+        Self {
+            exp: exp.timestamp(),
+            iat: iat.timestamp(),
+            jti: uuid::Uuid::new_v4().to_string(),
+            scope: None,
         }
+    }
 
+    /// Restricts the token to the given scope.
+    ///
+    /// * `scope`: scope of the token.
+    pub fn with_scope(self, scope: TokenScope) -> Self {
         Self {
-            exp: exp.timestamp(),
+            scope: Some(scope),
+            ..self
+        }
+    }
+
+    /// Determines whether the token allows an operation which requires the given scope.
+    ///
+    /// * `required`: scope required by the operation.
+    pub fn allows(&self, required: TokenScope) -> bool {
+        match self.scope {
+            Some(scope) => scope >= required,
+            None => true,
         }
     }
 }
 
+/// Scope of a token.
+///
+/// Each scope includes the previous ones. For instance, a token with the `Configure` scope can
+/// read the configuration too.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read the configuration and monitor the installation.
+    Read,
+    /// Change the configuration.
+    Configure,
+    /// Start the installation.
+    Install,
+}
+
+impl Display for TokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Read => "read",
+            Self::Configure => "configure",
+            Self::Install => "install",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Default for TokenClaims {
+    fn default() -> Self {
+        Self::new(Duration::try_days(1).unwrap_or_default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
 
-    use super::AuthToken;
+    use super::{is_local, AuthToken, TokenClaims, TokenScope, UserTokens};
+    use chrono::Duration;
 
     #[test]
     fn test_generate_token() {
@@ -202,6 +434,43 @@ mod tests {
         assert!(wrong.is_err())
     }
 
+    #[test]
+    fn test_generate_token_with_claims() {
+        let claims = TokenClaims::new(Duration::try_minutes(5).unwrap());
+        let token = AuthToken::generate_with_claims("nots3cr3t", &claims).unwrap();
+        let decoded = token.claims("nots3cr3t").unwrap();
+        assert_eq!(decoded.exp - decoded.iat, 300);
+        assert_eq!(decoded.jti, claims.jti);
+
+        let other = TokenClaims::new(Duration::try_minutes(5).unwrap());
+        assert_ne!(other.jti, claims.jti);
+    }
+
+    #[test]
+    fn test_token_without_jti() {
+        let claims = serde_json::json!({ "exp": chrono::Utc::now().timestamp() + 300 });
+        let encoding = jsonwebtoken::EncodingKey::from_secret("nots3cr3t".as_ref());
+        let token =
+            jsonwebtoken::encode(&jsonwebtoken::Header::default(), &claims, &encoding).unwrap();
+        let token = AuthToken::new(&token);
+        assert!(token.claims("nots3cr3t").is_err());
+    }
+
+    #[test]
+    fn test_token_scope() {
+        let claims = TokenClaims::default();
+        assert!(claims.allows(TokenScope::Install));
+
+        let claims = TokenClaims::default().with_scope(TokenScope::Configure);
+        assert!(claims.allows(TokenScope::Read));
+        assert!(claims.allows(TokenScope::Configure));
+        assert!(!claims.allows(TokenScope::Install));
+
+        let token = AuthToken::generate_with_claims("nots3cr3t", &claims).unwrap();
+        let decoded = token.claims("nots3cr3t").unwrap();
+        assert_eq!(decoded.scope, Some(TokenScope::Configure));
+    }
+
     #[test]
     fn test_write_and_read_token() {
         // let token = AuthToken::from_path<P: AsRef<Path>>(path: P)
@@ -216,4 +485,41 @@ mod tests {
         let decoded = read_token.claims("nots3cr3t");
         assert!(decoded.is_ok());
     }
+
+    #[test]
+    fn test_write_and_read_user_tokens() {
+        let local = AuthToken::generate("nots3cr3t").unwrap();
+        let remote = AuthToken::generate("s3cr3t").unwrap();
+
+        let mut tokens = UserTokens::default();
+        tokens.insert("http://localhost/api", &local);
+        tokens.insert("https://agama.example.net/api/", &remote);
+
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("tokens.json");
+        tokens.write(&path).unwrap();
+
+        let mut read_tokens = UserTokens::read(&path).unwrap();
+        let servers: Vec<_> = read_tokens.servers().collect();
+        assert_eq!(
+            servers,
+            vec!["http://localhost/api", "https://agama.example.net/api"]
+        );
+
+        let token = read_tokens.get("https://agama.example.net/api").unwrap();
+        assert!(token.claims("s3cr3t").is_ok());
+
+        assert!(read_tokens.remove("http://localhost/api"));
+        assert!(!read_tokens.remove("http://localhost/api"));
+        assert!(read_tokens.get("http://localhost/api").is_none());
+    }
+
+    #[test]
+    fn test_is_local() {
+        assert!(is_local("http://localhost/api"));
+        assert!(is_local("https://127.0.0.1/api"));
+        assert!(is_local("http://[::1]/api"));
+        assert!(!is_local("https://agama.example.net/api"));
+        assert!(!is_local("not a URL"));
+    }
 }
@@ -1,5 +1,9 @@
-use reqwest::{header, Response};
+use reqwest::{header, Certificate, Response};
 use serde::{de::DeserializeOwned, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    tungstenite::client::IntoClientRequest, Connector, MaybeTlsStream, WebSocketStream,
+};
 
 use crate::{auth::AuthToken, error::ServiceError};
 
@@ -20,11 +24,33 @@ use crate::{auth::AuthToken, error::ServiceError};
 ///     client.get("/questions").await
 ///   }
 /// ```
+///
+/// To talk to a remote server, set the base URL and the TLS options before authenticating:
+///
+/// ```no_run
+///   use agama_lib::base_http_client::BaseHTTPClient;
+///   use agama_lib::error::ServiceError;
+///
+///   fn remote_client() -> Result<BaseHTTPClient, ServiceError> {
+///     BaseHTTPClient::default()
+///       .with_base_url("https://agama.example.net/api")
+///       .insecure()?
+///       .authenticated()
+///   }
+/// ```
+#[derive(Clone)]
 pub struct BaseHTTPClient {
     client: reqwest::Client,
+    insecure: bool,
+    /// Additional trusted certificate (PEM format).
+    certificate: Option<Vec<u8>>,
+    token: Option<String>,
     pub base_url: String,
 }
 
+/// WebSocket connection to the HTTP API.
+pub type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
 const API_URL: &str = "http://localhost/api";
 
 impl Default for BaseHTTPClient {
@@ -34,6 +60,9 @@ impl Default for BaseHTTPClient {
     fn default() -> Self {
         Self {
             client: reqwest::Client::new(),
+            insecure: false,
+            certificate: None,
+            token: None,
             base_url: API_URL.to_owned(),
         }
     }
@@ -42,34 +71,131 @@ impl Default for BaseHTTPClient {
 impl BaseHTTPClient {
     /// Uses `localhost`, authenticates with [`AuthToken`].
     pub fn new() -> Result<Self, ServiceError> {
-        Ok(Self {
-            client: Self::authenticated_client()?,
-            ..Default::default()
-        })
+        Self::default().authenticated()
+    }
+
+    /// Uses the given URL (e.g., `https://agama.example.net/api`) as the base for the requests.
+    ///
+    /// * `url`: base URL of Agama's HTTP API.
+    pub fn with_base_url(self, url: &str) -> Self {
+        Self {
+            base_url: url.trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Accepts invalid TLS certificates (e.g., the self-signed one generated by the server).
+    pub fn insecure(self) -> Result<Self, ServiceError> {
+        Self {
+            insecure: true,
+            ..self
+        }
+        .rebuild()
+    }
+
+    /// Trusts the given PEM certificate when connecting through HTTPS.
+    ///
+    /// It is useful to verify the server's self-signed certificate instead of disabling the
+    /// verification at all.
+    ///
+    /// * `pem`: certificate in PEM format.
+    pub fn with_certificate(self, pem: &[u8]) -> Result<Self, ServiceError> {
+        // check that the certificate is valid
+        Certificate::from_pem(pem)?;
+        Self {
+            certificate: Some(pem.to_vec()),
+            ..self
+        }
+        .rebuild()
     }
 
-    fn authenticated_client() -> Result<reqwest::Client, ServiceError> {
+    /// Authenticates the requests with the [`AuthToken`] found for the current user and server.
+    ///
+    /// Set the base URL and the TLS options before calling this function.
+    pub fn authenticated(self) -> Result<Self, ServiceError> {
         // TODO: this error is subtly misleading, leading me to believe the SERVER said it,
         // but in fact it is the CLIENT not finding an auth token
-        let token = AuthToken::find().ok_or(ServiceError::NotAuthenticated)?;
+        let token = AuthToken::find_for(&self.base_url).ok_or(ServiceError::NotAuthenticated)?;
+        Self {
+            token: Some(token.to_string()),
+            ..self
+        }
+        .rebuild()
+    }
 
-        let mut headers = header::HeaderMap::new();
-        // just use generic anyhow error here as Bearer format is constructed by us, so failures can come only from token
-        let value = header::HeaderValue::from_str(format!("Bearer {}", token).as_str())
-            .map_err(anyhow::Error::new)?;
+    /// Authenticates the requests with the given [`AuthToken`].
+    ///
+    /// * `token`: token to use in the requests.
+    pub fn with_token(self, token: &AuthToken) -> Result<Self, ServiceError> {
+        Self {
+            token: Some(token.to_string()),
+            ..self
+        }
+        .rebuild()
+    }
+
+    /// Builds the underlying [`reqwest::Client`] according to the current options.
+    fn rebuild(self) -> Result<Self, ServiceError> {
+        let mut builder = reqwest::Client::builder().danger_accept_invalid_certs(self.insecure);
 
-        headers.insert(header::AUTHORIZATION, value);
+        if let Some(certificate) = &self.certificate {
+            builder = builder.add_root_certificate(Certificate::from_pem(certificate)?);
+        }
+
+        if let Some(token) = &self.token {
+            let mut headers = header::HeaderMap::new();
+            // just use generic anyhow error here as Bearer format is constructed by us, so failures can come only from token
+            let value = header::HeaderValue::from_str(format!("Bearer {}", token).as_str())
+                .map_err(anyhow::Error::new)?;
+            headers.insert(header::AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
 
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-        Ok(client)
+        Ok(Self {
+            client: builder.build()?,
+            ..self
+        })
     }
 
     fn url(&self, path: &str) -> String {
         self.base_url.clone() + path
     }
 
+    /// Opens a WebSocket connection to the given path, honoring the TLS options and the token.
+    ///
+    /// Arguments:
+    ///
+    /// * `path`: path relative to HTTP API like `/ws`
+    pub async fn websocket(&self, path: &str) -> Result<WebSocket, ServiceError> {
+        let url = self.url(path);
+        let url = match url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some(("http", rest)) => format!("ws://{rest}"),
+            _ => url,
+        };
+
+        let mut request = url.into_client_request()?;
+        if let Some(token) = &self.token {
+            let value = header::HeaderValue::from_str(format!("Bearer {}", token).as_str())
+                .map_err(anyhow::Error::new)?;
+            request.headers_mut().insert(header::AUTHORIZATION, value);
+        }
+
+        let mut tls = native_tls::TlsConnector::builder();
+        tls.danger_accept_invalid_certs(self.insecure);
+        if let Some(certificate) = &self.certificate {
+            let certificate =
+                native_tls::Certificate::from_pem(certificate).map_err(anyhow::Error::new)?;
+            tls.add_root_certificate(certificate);
+        }
+        let connector = Connector::NativeTls(tls.build().map_err(anyhow::Error::new)?);
+
+        let (socket, _) =
+            tokio_tungstenite::connect_async_tls_with_config(request, None, false, Some(connector))
+                .await?;
+        Ok(socket)
+    }
+
     /// Simple wrapper around [`Response`] to get object from response.
     ///
     /// Arguments:
@@ -18,6 +18,8 @@ pub enum ServiceError {
     ZVariant(#[from] zvariant::Error),
     #[error("Failed to communicate with the HTTP backend '{0}'")]
     HTTPError(#[from] reqwest::Error),
+    #[error("WebSocket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
     // it's fine to say only "Error" because the original
     // specific error will be printed too
     #[error("Error: {0}")]
@@ -35,12 +37,20 @@ pub enum ServiceError {
     InvalidJson(#[from] serde_json::Error),
     #[error("Could not perform action '{0}'")]
     UnsuccessfulAction(String),
+    #[error("Unknown settings section '{0}'")]
+    UnknownSection(String),
     #[error("Unknown installation phase: {0}")]
     UnknownInstallationPhase(u32),
     #[error("Question with id {0} does not exist")]
     QuestionNotExist(u32),
     #[error("Backend call failed with status {0} and text '{1}'")]
     BackendError(u16, String),
+    #[error("Could not apply the {0} settings, so the previous settings were restored: {1}")]
+    FailedSection(String, Box<ServiceError>),
+    #[error("Could not apply the {0} settings ({1}) nor restore the previous settings: {2}")]
+    FailedRollback(String, Box<ServiceError>, Box<ServiceError>),
+    #[error("The previous root password cannot be restored")]
+    CannotRestoreRootPassword,
     #[error("You are not logged in. Please use: agama auth login")]
     NotAuthenticated,
     // Specific error when something does not work as expected, but it is not user fault
@@ -48,6 +58,45 @@ pub enum ServiceError {
     InternalError(String),
 }
 
+impl From<tokio_tungstenite::tungstenite::Error> for ServiceError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(error))
+    }
+}
+
+impl ServiceError {
+    /// Returns an identifier for the kind of error.
+    ///
+    /// Unlike the error message, the identifier is stable, so programs can rely on it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::CannotGenerateLogs(_) => "cannot_generate_logs",
+            Self::DBus(_) => "dbus",
+            Self::DBusConnectionError(_, _) => "dbus_connection",
+            Self::DBusProtocol(_) => "dbus_protocol",
+            Self::ZVariant(_) => "dbus_type",
+            Self::HTTPError(_) => "http",
+            Self::WebSocket(_) => "websocket",
+            Self::Anyhow(_) => "generic",
+            Self::NetworkClientError(_) => "network_client",
+            Self::WrongUser(_) => "wrong_user",
+            Self::FailedRegistration(_) => "failed_registration",
+            Self::UnknownPatterns(_) => "unknown_patterns",
+            Self::InvalidJson(_) => "invalid_json",
+            Self::UnsuccessfulAction(_) => "unsuccessful_action",
+            Self::UnknownSection(_) => "unknown_section",
+            Self::UnknownInstallationPhase(_) => "unknown_installation_phase",
+            Self::QuestionNotExist(_) => "question_not_found",
+            Self::BackendError(_, _) => "backend",
+            Self::FailedSection(_, _) => "failed_section",
+            Self::FailedRollback(_, _, _) => "failed_rollback",
+            Self::CannotRestoreRootPassword => "cannot_restore_root_password",
+            Self::NotAuthenticated => "not_authenticated",
+            Self::InternalError(_) => "internal",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ProfileError {
     #[error("Could not read the profile")]
@@ -61,3 +110,16 @@ pub enum ProfileError {
     #[error("Error: {0}")]
     Anyhow(#[from] anyhow::Error),
 }
+
+impl ProfileError {
+    /// Returns an identifier for the kind of error (see [ServiceError::code]).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Unreachable(_) => "profile_unreachable",
+            Self::EvaluationError(_) => "profile_evaluation",
+            Self::InputOutputError(_) => "profile_io",
+            Self::FormatError(_) => "profile_format",
+            Self::Anyhow(_) => "profile_generic",
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Implements a client to receive the events emitted by Agama's web server.
+//!
+//! The web server sends the events as JSON documents through a WebSocket (`/api/ws`). Only the
+//! events that clients usually react to are modeled by [Event]. Use
+//! [EventsClient::receive_json] to get any event as JSON.
+//!
+//! By default, the server sends all the events. Clients can limit them by sending an
+//! [EventsRequest::Subscribe] message (see [EventsClient::subscribe]):
+//!
+//! ```json
+//! { "type": "Subscribe", "types": ["Progress"], "services": ["software"] }
+//! ```
+//!
+//! Each event includes an increasing `id`. After a disconnection, clients can use
+//! [EventsClient::connect_since] to get the events they missed. If the server cannot send them,
+//! the client receives an [Event::ResyncNeeded] and it should read the state again.
+//!
+//! ```no_run
+//!   use agama_lib::base_http_client::BaseHTTPClient;
+//!   use agama_lib::error::ServiceError;
+//!   use agama_lib::events::{Event, EventsClient};
+//!
+//!   async fn print_progress() -> Result<(), ServiceError> {
+//!     let client = BaseHTTPClient::new()?;
+//!     let mut events = EventsClient::connect(&client).await?;
+//!     while let Some(event) = events.receive().await? {
+//!       if let Event::Progress { service, progress } = event {
+//!         println!("{}: {}", service, progress.current_title);
+//!       }
+//!     }
+//!     Ok(())
+//!   }
+//! ```
+
+use crate::{
+    base_http_client::{BaseHTTPClient, WebSocket},
+    error::ServiceError,
+    issue::Issue,
+    manager::InstallationPhase,
+    progress::Progress,
+    questions::model::Question,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+
+/// D-Bus name of the manager service, as used in the events.
+pub const MANAGER_SERVICE: &str = "org.opensuse.Agama.Manager1";
+/// D-Bus name of the software service, as used in the events.
+pub const SOFTWARE_SERVICE: &str = "org.opensuse.Agama.Software1";
+
+/// Event emitted by Agama's web server.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum Event {
+    /// The progress of a service changed.
+    Progress {
+        service: String,
+        #[serde(flatten)]
+        progress: Progress,
+    },
+    /// The installation phase changed.
+    InstallationPhaseChanged { phase: InstallationPhase },
+    /// The status (idle or busy) of a service changed.
+    ServiceStatusChanged { service: String, status: u32 },
+    /// The list of issues of a service changed.
+    IssuesChanged {
+        service: String,
+        path: String,
+        issues: Vec<Issue>,
+    },
+    /// The list of questions changed.
+    QuestionsChanged,
+    /// A new question was asked.
+    QuestionAdded { question: Question },
+    /// A question was answered. The password, if any, is not included.
+    QuestionAnswered { id: u32, answer: String },
+    /// A question was removed.
+    QuestionRemoved { id: u32 },
+    /// Some events were lost, so the client should read the state again.
+    ResyncNeeded,
+    /// Any other event.
+    #[serde(other)]
+    Other,
+}
+
+/// Selects events by type and service.
+///
+/// An empty list means "any". The services match when the given name is included in the name of
+/// the service, ignoring the case (e.g., "software" matches "org.opensuse.Agama.Software1").
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventsFilter {
+    /// Types of the events (e.g., "Progress").
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Services emitting the events. The events that are not related to a service are
+    /// discarded.
+    #[serde(default)]
+    pub services: Vec<String>,
+}
+
+impl EventsFilter {
+    /// Whether the given event (in JSON) matches the filter.
+    ///
+    /// * `event`: event to check.
+    pub fn matches(&self, event: &serde_json::Value) -> bool {
+        if !self.types.is_empty() {
+            let Some(event_type) = event.get("type").and_then(|t| t.as_str()) else {
+                return false;
+            };
+            if !self.types.iter().any(|t| t == event_type) {
+                return false;
+            }
+        }
+
+        if !self.services.is_empty() {
+            let Some(service) = event.get("service").and_then(|s| s.as_str()) else {
+                return false;
+            };
+            let service = service.to_lowercase();
+            if !self
+                .services
+                .iter()
+                .any(|s| service.contains(&s.to_lowercase()))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Request sent by the clients through the WebSocket.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum EventsRequest {
+    /// Receive only the events matching the filter. It replaces any previous subscription.
+    Subscribe(EventsFilter),
+}
+
+/// Receives the events through the WebSocket.
+pub struct EventsClient {
+    socket: WebSocket,
+    last_id: Option<u64>,
+}
+
+impl EventsClient {
+    const PATH: &'static str = "/ws";
+
+    /// Connects to the web server.
+    ///
+    /// * `client`: HTTP client pointing to the server. It must be authenticated.
+    pub async fn connect(client: &BaseHTTPClient) -> Result<Self, ServiceError> {
+        let socket = client.websocket(Self::PATH).await?;
+        Ok(Self {
+            socket,
+            last_id: None,
+        })
+    }
+
+    /// Connects to the web server, receiving first the events after the given one.
+    ///
+    /// * `client`: HTTP client pointing to the server. It must be authenticated.
+    /// * `since`: identifier of the last received event (see [EventsClient::last_id]).
+    pub async fn connect_since(client: &BaseHTTPClient, since: u64) -> Result<Self, ServiceError> {
+        let path = format!("{}?since={}", Self::PATH, since);
+        let socket = client.websocket(&path).await?;
+        Ok(Self {
+            socket,
+            last_id: Some(since),
+        })
+    }
+
+    /// Identifier of the last received event.
+    pub fn last_id(&self) -> Option<u64> {
+        self.last_id
+    }
+
+    /// Asks the server to send only the events matching the given filter.
+    ///
+    /// * `filter`: events to receive.
+    pub async fn subscribe(&mut self, filter: &EventsFilter) -> Result<(), ServiceError> {
+        let request = EventsRequest::Subscribe(filter.clone());
+        let json = serde_json::to_string(&request)?;
+        self.socket.send(Message::Text(json)).await?;
+        Ok(())
+    }
+
+    /// Waits for the next event and returns it as JSON.
+    ///
+    /// It returns `None` when the server closes the connection.
+    pub async fn receive_json(&mut self) -> Result<Option<serde_json::Value>, ServiceError> {
+        while let Some(message) = self.socket.next().await {
+            match message? {
+                Message::Text(text) => {
+                    let json: serde_json::Value = serde_json::from_str(&text)?;
+                    if let Some(id) = json.get("id").and_then(|i| i.as_u64()) {
+                        self.last_id = Some(id);
+                    }
+                    return Ok(Some(json));
+                }
+                Message::Close(_) => return Ok(None),
+                _ => continue,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Waits for the next event.
+    ///
+    /// It returns `None` when the server closes the connection.
+    pub async fn receive(&mut self) -> Result<Option<Event>, ServiceError> {
+        let Some(json) = self.receive_json().await? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_value(json)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, EventsClient, EventsFilter};
+    use crate::{
+        auth::AuthToken, base_http_client::BaseHTTPClient, manager::InstallationPhase,
+        progress::Progress,
+    };
+    use futures_util::SinkExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::{
+        handshake::server::{Request, Response},
+        Message,
+    };
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn test_receive_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let check_auth = |request: &Request, response: Response| {
+                assert_eq!(request.uri().path(), "/api/ws");
+                let auth = request.headers().get("authorization").unwrap();
+                assert_eq!(auth, "Bearer 123456");
+                Ok(response)
+            };
+            let mut socket = tokio_tungstenite::accept_hdr_async(stream, check_auth)
+                .await
+                .unwrap();
+            let json = r#"{"type": "QuestionsChanged", "id": 7}"#;
+            socket.send(Message::Text(json.to_string())).await.unwrap();
+            socket.close(None).await.unwrap();
+        });
+
+        let client = BaseHTTPClient::default()
+            .with_base_url(&format!("http://{address}/api"))
+            .with_token(&AuthToken::new("123456"))
+            .unwrap();
+        let mut events = EventsClient::connect(&client).await.unwrap();
+        assert_eq!(
+            events.receive().await.unwrap(),
+            Some(Event::QuestionsChanged)
+        );
+        assert_eq!(events.last_id(), Some(7));
+        assert_eq!(events.receive().await.unwrap(), None);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_filter_events() {
+        let progress = serde_json::json!({
+            "type": "Progress", "service": "org.opensuse.Agama.Software1"
+        });
+        let questions = serde_json::json!({ "type": "QuestionsChanged" });
+
+        let filter = EventsFilter::default();
+        assert!(filter.matches(&progress));
+        assert!(filter.matches(&questions));
+
+        let filter = EventsFilter {
+            types: vec!["Progress".to_string(), "IssuesChanged".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches(&progress));
+        assert!(!filter.matches(&questions));
+
+        let filter = EventsFilter {
+            services: vec!["software".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches(&progress));
+        assert!(!filter.matches(&questions));
+
+        let filter = EventsFilter {
+            services: vec!["storage".to_string()],
+            ..Default::default()
+        };
+        assert!(!filter.matches(&progress));
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let json = r#"{"type": "Progress", "service": "org.opensuse.Agama.Manager1",
+            "currentStep": 1, "maxSteps": 3, "currentTitle": "Probing", "finished": false}"#;
+        let event: Event = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            event,
+            Event::Progress {
+                service: "org.opensuse.Agama.Manager1".to_string(),
+                progress: Progress {
+                    current_step: 1,
+                    max_steps: 3,
+                    current_title: "Probing".to_string(),
+                    finished: false
+                }
+            }
+        );
+
+        let json = r#"{"type": "InstallationPhaseChanged", "phase": 2}"#;
+        let event: Event = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            event,
+            Event::InstallationPhaseChanged {
+                phase: InstallationPhase::Install
+            }
+        );
+
+        let json = r#"{"type": "QuestionAnswered", "id": 3, "answer": "yes"}"#;
+        let event: Event = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            event,
+            Event::QuestionAnswered {
+                id: 3,
+                answer: "yes".to_string()
+            }
+        );
+
+        let json = r#"{"type": "LocaleChanged", "locale": "es_ES"}"#;
+        let event: Event = serde_json::from_str(json).unwrap();
+        assert_eq!(event, Event::Other);
+    }
+}
@@ -12,6 +12,11 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
+mod diff;
+mod http_client;
+pub use diff::{SettingsChange, SettingsDiff};
+pub use http_client::InstallSettingsHTTPClient;
+
 /// Installation settings
 ///
 /// This struct represents installation settings. It serves as an entry point and it is composed of
@@ -0,0 +1,143 @@
+//! Implements a client to read and write the whole installation settings.
+use super::InstallSettings;
+use crate::base_http_client::BaseHTTPClient;
+use crate::error::ServiceError;
+
+/// Reads and writes the complete installation settings through the `/config` endpoint.
+///
+/// Unlike [crate::Store], it needs a single request to read or write the whole profile.
+pub struct InstallSettingsHTTPClient {
+    client: BaseHTTPClient,
+}
+
+impl InstallSettingsHTTPClient {
+    pub fn new() -> Result<Self, ServiceError> {
+        Ok(Self {
+            client: BaseHTTPClient::new()?,
+        })
+    }
+
+    pub fn new_with_base(base: BaseHTTPClient) -> Self {
+        Self { client: base }
+    }
+
+    /// Returns the current installation settings.
+    pub async fn get_config(&self) -> Result<InstallSettings, ServiceError> {
+        self.client.get("/config").await
+    }
+
+    /// Applies the given installation settings.
+    ///
+    /// * `settings`: installation settings to apply.
+    pub async fn set_config(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        self.client.put_void("/config", settings).await
+    }
+
+    /// Applies the given installation settings in an all-or-nothing fashion.
+    ///
+    /// If any section cannot be applied, the server restores the previous settings.
+    ///
+    /// * `settings`: installation settings to apply.
+    pub async fn set_config_atomic(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        self.client.put_void("/config?atomic=true", settings).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use httpmock::prelude::*;
+    use std::error::Error;
+    use tokio::test; // without this, "error: async functions cannot be used for tests"
+
+    fn settings_client(mock_server_url: String) -> InstallSettingsHTTPClient {
+        let mut bhc = BaseHTTPClient::default();
+        bhc.base_url = mock_server_url;
+        InstallSettingsHTTPClient::new_with_base(bhc)
+    }
+
+    #[test]
+    async fn test_getting_config() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let config_mock = server.mock(|when, then| {
+            when.method(GET).path("/api/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "software": { "patterns": ["gnome"] },
+                    "localization": { "language": "en_US.UTF-8" }
+                }"#,
+                );
+        });
+        let url = server.url("/api");
+
+        let client = settings_client(url);
+        let settings = client.get_config().await?;
+
+        // main assertion
+        let software = settings.software.unwrap();
+        assert_eq!(software.patterns, vec!["gnome".to_string()]);
+        assert!(settings.product.is_none());
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        config_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_setting_config() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let config_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/config")
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "user": null,
+                    "root": null,
+                    "software": { "patterns": ["gnome"] },
+                    "product": null,
+                    "network": null,
+                    "localization": null
+                }));
+            then.status(200);
+        });
+        let url = server.url("/api");
+
+        let client = settings_client(url);
+        let settings: InstallSettings =
+            serde_json::from_str(r#"{ "software": { "patterns": ["gnome"] } }"#)?;
+        let result = client.set_config(&settings).await;
+
+        // main assertion
+        result?;
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        config_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_setting_config_atomic() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let config_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/config")
+                .query_param("atomic", "true");
+            then.status(400)
+                .header("content-type", "application/json")
+                .body(r#"{"error": "Could not apply the software settings"}"#);
+        });
+        let url = server.url("/api");
+
+        let client = settings_client(url);
+        let result = client.set_config_atomic(&InstallSettings::default()).await;
+
+        // main assertion
+        assert!(matches!(result, Err(ServiceError::BackendError(400, _))));
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        config_mock.assert();
+        Ok(())
+    }
+}
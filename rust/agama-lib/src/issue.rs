@@ -0,0 +1,138 @@
+//! Representation of the issues reported by Agama services.
+//!
+//! An issue is a problem that prevents the installation from starting, like a missing product
+//! or an invalid storage setup.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Represents an issue.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    /// Human-readable description.
+    pub description: String,
+    /// Additional details, if any.
+    pub details: Option<String>,
+    /// Source of the issue (as defined by the D-Bus API).
+    pub source: u32,
+    /// Severity of the issue (as defined by the D-Bus API).
+    pub severity: u32,
+}
+
+impl Issue {
+    /// Builds an issue from the tuple used by the D-Bus API.
+    pub fn from_tuple(
+        (description, details, source, severity): (String, String, u32, u32),
+    ) -> Self {
+        let details = if details.is_empty() {
+            None
+        } else {
+            Some(details)
+        };
+
+        Self {
+            description,
+            details,
+            source,
+            severity,
+        }
+    }
+}
+
+/// Source of an issue.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSource {
+    /// The source is unknown.
+    Unknown,
+    /// The system (e.g., there are no disks).
+    System,
+    /// The configuration (e.g., no product was selected).
+    Config,
+}
+
+impl From<u32> for IssueSource {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::System,
+            2 => Self::Config,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Severity of an issue.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// It does not prevent the installation.
+    Warn,
+    /// It prevents the installation.
+    Error,
+}
+
+impl From<u32> for IssueSeverity {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Warn,
+            _ => Self::Error,
+        }
+    }
+}
+
+impl fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Issue with its source and severity decoded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IssueSummary {
+    /// Human-readable description.
+    pub description: String,
+    /// Additional details, if any.
+    pub details: Option<String>,
+    /// Source of the issue.
+    pub source: IssueSource,
+    /// Severity of the issue.
+    pub severity: IssueSeverity,
+}
+
+impl From<Issue> for IssueSummary {
+    fn from(issue: Issue) -> Self {
+        Self {
+            description: issue.description,
+            details: issue.details,
+            source: issue.source.into(),
+            severity: issue.severity.into(),
+        }
+    }
+}
+
+/// Issues of all the services, grouped by service (e.g., "storage" or "product").
+pub type IssuesByService = BTreeMap<String, Vec<IssueSummary>>;
+
+#[cfg(test)]
+mod tests {
+    use super::{Issue, IssueSeverity, IssueSource, IssueSummary};
+
+    #[test]
+    fn test_decode_issue() {
+        let issue = Issue::from_tuple(("No product".to_string(), "".to_string(), 2, 1));
+        let summary = IssueSummary::from(issue);
+        assert_eq!(summary.source, IssueSource::Config);
+        assert_eq!(summary.severity, IssueSeverity::Error);
+        assert_eq!(summary.details, None);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["source"], "config");
+        assert_eq!(json["severity"], "error");
+
+        assert_eq!(IssueSource::from(0), IssueSource::Unknown);
+        assert_eq!(IssueSeverity::from(0), IssueSeverity::Warn);
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! * Reading and writing [installation settings](install_settings::InstallSettings).
 //! * Monitoring the [progress].
+//! * Receiving the [events] emitted by the web server.
 //! * Triggering actions through the [manager] (e.g., starting installation).
 //!
 //! ## Handling installation settings
@@ -26,7 +27,9 @@
 pub mod auth;
 pub mod base_http_client;
 pub mod error;
+pub mod events;
 pub mod install_settings;
+pub mod issue;
 pub mod jobs;
 pub mod localization;
 pub mod manager;
@@ -41,10 +44,9 @@ pub mod dbus;
 pub mod progress;
 pub mod proxies;
 mod store;
-pub use store::Store;
+pub use store::{Section, Store};
 pub mod questions;
 use crate::error::ServiceError;
-use reqwest::{header, Client};
 
 const ADDRESS: &str = "unix:path=/run/agama/bus";
 
@@ -59,18 +61,3 @@ pub async fn connection_to(address: &str) -> Result<zbus::Connection, ServiceErr
         .map_err(|e| ServiceError::DBusConnectionError(address.to_string(), e))?;
     Ok(connection)
 }
-
-pub fn http_client(token: &str) -> Result<reqwest::Client, ServiceError> {
-    let mut headers = header::HeaderMap::new();
-    let value = header::HeaderValue::from_str(format!("Bearer {}", token).as_str())
-        .map_err(|e| ServiceError::NetworkClientError(e.to_string()))?;
-
-    headers.insert(header::AUTHORIZATION, value);
-
-    let client = Client::builder()
-        .default_headers(headers)
-        .build()
-        .map_err(|e| ServiceError::NetworkClientError(e.to_string()))?;
-
-    Ok(client)
-}
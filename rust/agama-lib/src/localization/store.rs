@@ -2,18 +2,19 @@
 // TODO: for an overview see crate::store (?)
 
 use super::{LocalizationHTTPClient, LocalizationSettings};
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
 use crate::localization::model::LocaleConfig;
 
-/// Loads and stores the storage settings from/to the D-Bus service.
+/// Loads and stores the localization settings from/to the HTTP API.
 pub struct LocalizationStore {
     localization_client: LocalizationHTTPClient,
 }
 
 impl LocalizationStore {
-    pub fn new() -> Result<LocalizationStore, ServiceError> {
+    pub fn new(client: BaseHTTPClient) -> Result<LocalizationStore, ServiceError> {
         Ok(Self {
-            localization_client: LocalizationHTTPClient::new()?,
+            localization_client: LocalizationHTTPClient::new_with_base(client)?,
         })
     }
 
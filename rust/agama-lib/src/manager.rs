@@ -1,15 +1,21 @@
 //! This module implements the web API for the manager module.
 
 use crate::error::ServiceError;
+use crate::issue::{IssueSeverity, IssuesByService};
 use crate::proxies::ServiceStatusProxy;
 use crate::{
     progress::Progress,
     proxies::{Manager1Proxy, ProgressProxy},
 };
-use serde_repr::Serialize_repr;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::fmt;
 use tokio_stream::StreamExt;
 use zbus::Connection;
 
+pub mod http_client;
+pub use http_client::ManagerHTTPClient;
+
 /// D-Bus client for the manager service
 #[derive(Clone)]
 pub struct ManagerClient<'a> {
@@ -20,7 +26,7 @@ pub struct ManagerClient<'a> {
 
 /// Represents the installation phase.
 /// NOTE: does this conversion have any value?
-#[derive(Clone, Copy, Debug, PartialEq, Serialize_repr, utoipa::ToSchema)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize_repr, Deserialize_repr, utoipa::ToSchema)]
 #[repr(u32)]
 pub enum InstallationPhase {
     /// Start up phase.
@@ -31,6 +37,71 @@ pub enum InstallationPhase {
     Install,
 }
 
+/// Holds information about the manager's status.
+#[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallerStatus {
+    /// Current installation phase.
+    pub phase: InstallationPhase,
+    /// Whether the service is busy.
+    pub is_busy: bool,
+    /// Whether Agama is running on Iguana.
+    pub use_iguana: bool,
+    /// Whether it is possible to start the installation.
+    pub can_install: bool,
+    /// Why it is not possible to start the installation (empty if it is possible).
+    #[serde(default)]
+    pub blockers: InstallationBlockers,
+}
+
+/// Reasons why the installation cannot start.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallationBlockers {
+    /// D-Bus names of the busy services.
+    pub busy_services: Vec<String>,
+    /// Issues with "error" severity, grouped by service.
+    #[schema(value_type = Object)]
+    pub issues: IssuesByService,
+}
+
+impl InstallationBlockers {
+    /// Builds the list of blockers, discarding the issues which are not errors.
+    ///
+    /// * `busy_services`: busy services.
+    /// * `issues`: issues of all the services.
+    pub fn new(busy_services: Vec<String>, mut issues: IssuesByService) -> Self {
+        for service_issues in issues.values_mut() {
+            service_issues.retain(|i| i.severity == IssueSeverity::Error);
+        }
+        issues.retain(|_, service_issues| !service_issues.is_empty());
+
+        Self {
+            busy_services,
+            issues,
+        }
+    }
+
+    /// Whether there are no blockers.
+    pub fn is_empty(&self) -> bool {
+        self.busy_services.is_empty() && self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for InstallationBlockers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (service, issues) in &self.issues {
+            for issue in issues {
+                writeln!(f, "{}: {}", service, issue.description)?;
+            }
+        }
+        for service in &self.busy_services {
+            writeln!(f, "{} is busy", service)?;
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<u32> for InstallationPhase {
     type Error = ServiceError;
 
@@ -120,3 +191,39 @@ impl<'a> ManagerClient<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::InstallationBlockers;
+    use crate::issue::{Issue, IssuesByService};
+
+    fn issue(description: &str, severity: u32) -> Issue {
+        Issue::from_tuple((description.to_string(), "".to_string(), 2, severity))
+    }
+
+    #[test]
+    fn test_installation_blockers() {
+        let mut issues = IssuesByService::new();
+        issues.insert(
+            "storage".to_string(),
+            vec![
+                issue("No target disk selected", 1).into(),
+                issue("Small swap", 0).into(),
+            ],
+        );
+        issues.insert("software".to_string(), vec![issue("Old repo", 0).into()]);
+        issues.insert("users".to_string(), vec![]);
+
+        let busy = vec!["org.opensuse.Agama.Software1".to_string()];
+        let blockers = InstallationBlockers::new(busy, issues);
+        assert_eq!(blockers.issues.len(), 1);
+        assert!(!blockers.is_empty());
+        assert_eq!(
+            blockers.to_string(),
+            "storage: No target disk selected\norg.opensuse.Agama.Software1 is busy\n"
+        );
+
+        let blockers = InstallationBlockers::new(vec![], IssuesByService::new());
+        assert!(blockers.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use super::InstallerStatus;
+use crate::{
+    base_http_client::BaseHTTPClient, error::ServiceError, issue::IssuesByService,
+    progress::Progress,
+};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// HTTP client for the manager service
+pub struct ManagerHTTPClient {
+    client: BaseHTTPClient,
+}
+
+impl ManagerHTTPClient {
+    pub fn new() -> Result<Self, ServiceError> {
+        Ok(Self {
+            client: BaseHTTPClient::new()?,
+        })
+    }
+
+    pub fn new_with_base(base: BaseHTTPClient) -> Self {
+        Self { client: base }
+    }
+
+    /// Returns the installer status (phase, whether it is busy, etc.).
+    pub async fn status(&self) -> Result<InstallerStatus, ServiceError> {
+        self.client.get("/manager/installer").await
+    }
+
+    /// Returns whether the manager is busy.
+    pub async fn is_busy(&self) -> Result<bool, ServiceError> {
+        Ok(self.status().await?.is_busy)
+    }
+
+    /// Determines whether it is possible to start the installation.
+    pub async fn can_install(&self) -> Result<bool, ServiceError> {
+        Ok(self.status().await?.can_install)
+    }
+
+    /// Starts the probing process.
+    ///
+    /// The server runs the probing in the background, so this function returns immediately.
+    pub async fn probe(&self) -> Result<(), ServiceError> {
+        self.client.post_void("/manager/probe", &()).await
+    }
+
+    /// Starts the installation.
+    pub async fn install(&self) -> Result<(), ServiceError> {
+        self.client.post_void("/manager/install", &()).await
+    }
+
+    /// Executes the after installation tasks.
+    pub async fn finish(&self) -> Result<(), ServiceError> {
+        self.client.post_void("/manager/finish", &()).await
+    }
+
+    /// Returns the issues of all the services.
+    pub async fn issues(&self) -> Result<IssuesByService, ServiceError> {
+        self.client.get("/manager/issues").await
+    }
+
+    /// Returns the current progress of the manager.
+    pub async fn progress(&self) -> Result<Progress, ServiceError> {
+        self.client.get("/manager/progress").await
+    }
+
+    /// Waits until the manager is idle.
+    ///
+    /// There is no way to get notified through HTTP, so it polls the installer status.
+    pub async fn wait(&self) -> Result<(), ServiceError> {
+        while self.is_busy().await? {
+            sleep(Duration::from_secs(1)).await;
+        }
+        Ok(())
+    }
+}
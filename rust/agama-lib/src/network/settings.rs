@@ -14,7 +14,7 @@ pub struct NetworkSettings {
     pub connections: Vec<NetworkConnection>,
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct MatchSettings {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub driver: Vec<String>,
@@ -49,8 +49,9 @@ pub struct BondSettings {
     pub mode: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub ports: Vec<String>,
+    /// Ports of the controller (if not given, the current ones are kept)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports: Option<Vec<String>>,
 }
 
 impl Default for BondSettings {
@@ -58,11 +59,138 @@ impl Default for BondSettings {
         Self {
             mode: "balance-rr".to_string(),
             options: None,
-            ports: vec![],
+            ports: None,
         }
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VlanSettings {
+    /// Name of the parent interface
+    pub parent: String,
+    /// VLAN identifier
+    pub id: u32,
+    /// VLAN protocol ("802.1Q" or "802.1ad")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_delay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hello_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ageing_time: Option<u32>,
+    /// Ports of the controller (if not given, the current ones are kept)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports: Option<Vec<String>>,
+}
+
+/// Settings of a bridge port
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgePortSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_cost: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfinibandSettings {
+    /// Partition key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p_key: Option<i32>,
+    /// Name of the parent interface (only for partitions)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Transport mode ("datagram" or "connected")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport_mode: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TunSettings {
+    /// Device mode ("tun" or "tap")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Group owning the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// User owning the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+/// 802.1X (EAP) authentication settings
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IEEE8021XSettings {
+    /// EAP methods (e.g., "tls", "peap" or "ttls")
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub eap: Vec<String>,
+    /// Inner authentication method for PEAP and TTLS (e.g., "mschapv2")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase2_auth: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymous_identity: Option<String>,
+    /// CA certificate, as a path or as inline PEM data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+    /// Client certificate, as a path or as inline PEM data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// Client private key, as a path or as inline PEM data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_password: Option<String>,
+}
+
+/// Static route
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteSettings {
+    /// Destination network (e.g., "10.0.0.0/8")
+    pub destination: IpInet,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_hop: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+    /// Routing table to add the route to (the main one by default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<u32>,
+}
+
+/// Routing policy rule
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoutingRuleSettings {
+    /// Priority of the rule (the lower, the sooner it is evaluated)
+    pub priority: u32,
+    /// Source network to match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<IpInet>,
+    /// Destination network to match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<IpInet>,
+    /// Routing table to look up for the matching traffic
+    pub table: u32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NetworkDevice {
     pub id: String,
@@ -82,6 +210,14 @@ pub struct NetworkConnection {
     pub method6: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gateway6: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routes4: Option<Vec<RouteSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routes6: Option<Vec<RouteSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_rules4: Option<Vec<RoutingRuleSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_rules6: Option<Vec<RoutingRuleSettings>>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub addresses: Vec<IpInet>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -94,12 +230,25 @@ pub struct NetworkConnection {
     pub wireless: Option<WirelessSettings>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interface: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
     pub match_settings: Option<MatchSettings>,
+    /// ID or interface name of the controller connection (e.g., a bond)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bond: Option<BondSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<VlanSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<BridgeSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_port: Option<BridgePortSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ieee_8021x: Option<IEEE8021XSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub infiniband: Option<InfinibandSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tun: Option<TunSettings>,
     #[serde(rename = "mac-address", skip_serializing_if = "Option::is_none")]
     pub mac_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -122,8 +271,25 @@ impl NetworkConnection {
             DeviceType::Wireless
         } else if self.bond.is_some() {
             DeviceType::Bond
+        } else if self.vlan.is_some() {
+            DeviceType::Vlan
+        } else if self.bridge.is_some() {
+            DeviceType::Bridge
+        } else if self.infiniband.is_some() {
+            DeviceType::Infiniband
+        } else if self.tun.is_some() {
+            DeviceType::Tun
         } else {
             DeviceType::Ethernet
         }
     }
+
+    /// Ports of the connection, if it is a controller (a bond or a bridge) and they are given.
+    pub fn ports(&self) -> Option<&[String]> {
+        if let Some(bond) = &self.bond {
+            bond.ports.as_deref()
+        } else {
+            self.bridge.as_ref().and_then(|b| b.ports.as_deref())
+        }
+    }
 }
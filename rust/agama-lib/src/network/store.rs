@@ -1,14 +1,15 @@
 use super::settings::NetworkConnection;
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
 use crate::network::{NetworkClient, NetworkSettings};
 
-/// Loads and stores the network settings from/to the D-Bus service.
+/// Loads and stores the network settings from/to the HTTP API.
 pub struct NetworkStore {
     network_client: NetworkClient,
 }
 
 impl NetworkStore {
-    pub async fn new(client: reqwest::Client) -> Result<NetworkStore, ServiceError> {
+    pub async fn new(client: BaseHTTPClient) -> Result<NetworkStore, ServiceError> {
         Ok(Self {
             network_client: NetworkClient::new(client).await?,
         })
@@ -34,6 +35,29 @@ impl NetworkStore {
 
         Ok(())
     }
+
+    /// Restores the previous network settings.
+    ///
+    /// The connections that were added when storing `applied` are removed, as they are not
+    /// included in the `previous` settings.
+    ///
+    /// * `previous`: settings to restore.
+    /// * `applied`: settings that were stored.
+    pub async fn restore(
+        &self,
+        previous: &NetworkSettings,
+        applied: &NetworkSettings,
+    ) -> Result<(), ServiceError> {
+        let added: Vec<String> = ordered_connections(&applied.connections)
+            .into_iter()
+            .filter(|id| !previous.connections.iter().any(|c| c.id == *id))
+            .collect();
+        let current = self.network_client.connections().await?;
+        for conn in current.iter().filter(|c| added.contains(&c.id)) {
+            self.network_client.remove_connection(&conn.id).await?;
+        }
+        self.store(previous).await
+    }
 }
 
 /// Returns the list of connections in the order they should be written to the D-Bus service.
@@ -57,21 +81,44 @@ fn add_ordered_connection(
     conns: &Vec<NetworkConnection>,
     ordered: &mut Vec<String>,
 ) {
-    if let Some(bond) = &conn.bond {
-        for port in &bond.ports {
-            if let Some(conn) = find_connection(port, conns) {
-                add_ordered_connection(conn, conns, ordered);
+    if ordered.contains(&conn.id) {
+        return;
+    }
+
+    if let Some(ports) = conn.ports() {
+        for port in ports {
+            if let Some(port_conn) = find_connection(port, conns) {
+                // the connection is written after its parent
+                if !is_parent(conn, port_conn) {
+                    add_ordered_connection(port_conn, conns, ordered);
+                }
             } else if !ordered.contains(&conn.id) {
                 ordered.push(port.clone());
             }
         }
     }
 
+    if let Some(parent) = conn.parent.as_ref().and_then(|p| find_connection(p, conns)) {
+        if parent.id != conn.id && !is_parent(conn, parent) {
+            add_ordered_connection(parent, conns, ordered);
+        }
+    }
+
     if !ordered.contains(&conn.id) {
         ordered.push(conn.id.to_owned())
     }
 }
 
+/// Whether a connection is the parent of another one.
+///
+/// * `parent`: the potential parent.
+/// * `conn`: connection to check.
+fn is_parent(parent: &NetworkConnection, conn: &NetworkConnection) -> bool {
+    conn.parent
+        .as_ref()
+        .is_some_and(|p| *p == parent.id || Some(p) == parent.interface.as_ref())
+}
+
 /// Finds a connection by id in the list.
 ///
 /// * `id`: connection ID.
@@ -91,15 +138,23 @@ fn default_connection(id: &str) -> NetworkConnection {
 
 #[cfg(test)]
 mod tests {
-    use super::ordered_connections;
-    use crate::network::settings::{BondSettings, NetworkConnection};
+    use super::{ordered_connections, NetworkStore};
+    use crate::base_http_client::BaseHTTPClient;
+    use crate::network::settings::{BondSettings, BridgeSettings, NetworkConnection};
+    use crate::network::NetworkSettings;
+    use httpmock::prelude::*;
+    use std::error::Error;
 
     #[test]
     fn test_ordered_connections() {
         let bond = NetworkConnection {
             id: "bond0".to_string(),
             bond: Some(BondSettings {
-                ports: vec!["eth0".to_string(), "eth1".to_string(), "eth3".to_string()],
+                ports: Some(vec![
+                    "eth0".to_string(),
+                    "eth1".to_string(),
+                    "eth3".to_string(),
+                ]),
                 ..Default::default()
             }),
             ..Default::default()
@@ -132,4 +187,101 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_ordered_connections_with_parent() {
+        let eth0 = NetworkConnection {
+            id: "eth0".to_string(),
+            parent: Some("br0".to_string()),
+            ..Default::default()
+        };
+        let bridge = NetworkConnection {
+            id: "Bridge".to_string(),
+            interface: Some("br0".to_string()),
+            bridge: Some(BridgeSettings::default()),
+            ..Default::default()
+        };
+        let bond = NetworkConnection {
+            id: "bond0".to_string(),
+            bond: Some(BondSettings {
+                ports: Some(vec!["eth1".to_string(), "eth2".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let eth1 = NetworkConnection {
+            id: "eth1".to_string(),
+            parent: Some("bond0".to_string()),
+            ..Default::default()
+        };
+
+        let conns = vec![eth0, bridge, eth1, bond];
+        let ordered = ordered_connections(&conns);
+        assert_eq!(
+            ordered,
+            vec![
+                "Bridge".to_string(),
+                "eth0".to_string(),
+                "eth2".to_string(),
+                "bond0".to_string(),
+                "eth1".to_string(),
+            ]
+        )
+    }
+
+    #[tokio::test]
+    async fn test_restore() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/api/network/connections");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"[{"id": "eth0"}, {"id": "eth1"}, {"id": "eth2"}]"#);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/api/network/connections/eth0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"id": "eth0"}"#);
+        });
+        let update_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/network/connections/eth0")
+                .body(r#"{"id":"eth0"}"#);
+            then.status(204);
+        });
+        let delete_mock = server.mock(|when, then| {
+            when.method(DELETE).path("/api/network/connections/eth1");
+            then.status(204);
+        });
+        let apply_mock = server.mock(|when, then| {
+            when.method(POST).path("/api/network/system/apply");
+            then.status(204);
+        });
+
+        let mut bhc = BaseHTTPClient::default();
+        bhc.base_url = server.url("/api");
+        let store = NetworkStore::new(bhc).await?;
+        let eth0 = NetworkConnection {
+            id: "eth0".to_string(),
+            ..Default::default()
+        };
+        let eth1 = NetworkConnection {
+            id: "eth1".to_string(),
+            ..Default::default()
+        };
+        let previous = NetworkSettings {
+            connections: vec![eth0.clone()],
+        };
+        let applied = NetworkSettings {
+            connections: vec![eth0, eth1],
+        };
+        store.restore(&previous, &applied).await?;
+
+        // eth2 was not added by the applied settings, so it is kept
+        delete_mock.assert();
+        update_mock.assert();
+        apply_mock.assert();
+        Ok(())
+    }
 }
@@ -56,6 +56,8 @@ pub enum DeviceType {
     Bond = 4,
     Vlan = 5,
     Bridge = 6,
+    Infiniband = 7,
+    Tun = 8,
 }
 
 // For now this mirrors NetworkManager, because it was less mental work than coming up with
@@ -263,6 +265,8 @@ impl TryFrom<u8> for DeviceType {
             4 => Ok(DeviceType::Bond),
             5 => Ok(DeviceType::Vlan),
             6 => Ok(DeviceType::Bridge),
+            7 => Ok(DeviceType::Infiniband),
+            8 => Ok(DeviceType::Tun),
             _ => Err(InvalidDeviceType(value)),
         }
     }
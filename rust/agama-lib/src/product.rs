@@ -1,10 +1,13 @@
 //! Implements support for handling the product settings
 
 mod client;
+mod http_client;
+pub mod model;
 pub mod proxies;
 mod settings;
 mod store;
 
 pub use client::{Product, ProductClient, RegistrationRequirement};
+pub use http_client::ProductHTTPClient;
 pub use settings::ProductSettings;
 pub use store::ProductStore;
@@ -0,0 +1,69 @@
+use super::model::{FailureDetails, RegistrationInfo, RegistrationParams};
+use crate::software::model::SoftwareConfig;
+use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+
+pub struct ProductHTTPClient {
+    client: BaseHTTPClient,
+}
+
+impl ProductHTTPClient {
+    pub fn new() -> Result<Self, ServiceError> {
+        Ok(Self {
+            client: BaseHTTPClient::new()?,
+        })
+    }
+
+    pub fn new_with_base(base: BaseHTTPClient) -> Self {
+        Self { client: base }
+    }
+
+    pub async fn get_software(&self) -> Result<SoftwareConfig, ServiceError> {
+        self.client.get("/software/config").await
+    }
+
+    pub async fn set_software(&self, config: &SoftwareConfig) -> Result<(), ServiceError> {
+        self.client.put_void("/software/config", config).await
+    }
+
+    /// Returns the id of the selected product to install
+    pub async fn product(&self) -> Result<String, ServiceError> {
+        let config = self.get_software().await?;
+        Ok(config.product.unwrap_or_default())
+    }
+
+    /// Selects the product to install
+    pub async fn select_product(&self, product_id: &str) -> Result<(), ServiceError> {
+        let config = SoftwareConfig {
+            product: Some(product_id.to_owned()),
+            patterns: None,
+        };
+        self.set_software(&config).await
+    }
+
+    pub async fn get_registration(&self) -> Result<RegistrationInfo, ServiceError> {
+        self.client.get("/software/registration").await
+    }
+
+    /// Registers the product.
+    ///
+    /// Returns the same (id, message) pair than the D-Bus API. When the server reports a failure
+    /// (422), the details are taken from the response body.
+    pub async fn register(&self, key: &str, email: &str) -> Result<(u32, String), ServiceError> {
+        let params = RegistrationParams {
+            key: key.to_owned(),
+            email: email.to_owned(),
+        };
+        let result = self
+            .client
+            .post_void("/software/registration", &params)
+            .await;
+        match result {
+            Ok(()) => Ok((0, "".to_string())),
+            Err(ServiceError::BackendError(422, ref body)) => {
+                let details: FailureDetails = serde_json::from_str(body)?;
+                Ok((details.id, details.message))
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
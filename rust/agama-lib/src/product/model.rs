@@ -0,0 +1,32 @@
+use crate::product::RegistrationRequirement;
+use serde::{Deserialize, Serialize};
+
+/// Information about registration configuration (product, patterns, etc.).
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationInfo {
+    /// Registration key. Empty value mean key not used or not registered.
+    pub key: String,
+    /// Registration email. Empty value mean email not used or not registered.
+    pub email: String,
+    /// if registration is required, optional or not needed for current product.
+    /// Change only if selected product is changed.
+    pub requirement: RegistrationRequirement,
+}
+
+/// Software service configuration (product, patterns, etc.).
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RegistrationParams {
+    /// Registration key.
+    pub key: String,
+    /// Registration email.
+    pub email: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FailureDetails {
+    /// ID of error. See dbus API for possible values
+    pub id: u32,
+    /// human readable error string intended to be displayed to user
+    pub message: String,
+}
@@ -1,33 +1,32 @@
 //! Implements the store for the product settings.
 
-use super::{ProductClient, ProductSettings};
+use super::{ProductHTTPClient, ProductSettings};
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
-use crate::manager::ManagerClient;
-use zbus::Connection;
+use crate::manager::ManagerHTTPClient;
 
-/// Loads and stores the product settings from/to the D-Bus service.
-pub struct ProductStore<'a> {
-    product_client: ProductClient<'a>,
-    manager_client: ManagerClient<'a>,
+/// Loads and stores the product settings from/to the HTTP API.
+pub struct ProductStore {
+    product_client: ProductHTTPClient,
+    manager_client: ManagerHTTPClient,
 }
 
-impl<'a> ProductStore<'a> {
-    pub async fn new(connection: Connection) -> Result<ProductStore<'a>, ServiceError> {
+impl ProductStore {
+    pub fn new(client: BaseHTTPClient) -> Result<ProductStore, ServiceError> {
         Ok(Self {
-            product_client: ProductClient::new(connection.clone()).await?,
-            manager_client: ManagerClient::new(connection).await?,
+            product_client: ProductHTTPClient::new_with_base(client.clone()),
+            manager_client: ManagerHTTPClient::new_with_base(client),
         })
     }
 
     pub async fn load(&self) -> Result<ProductSettings, ServiceError> {
         let product = self.product_client.product().await?;
-        let registration_code = self.product_client.registration_code().await?;
-        let email = self.product_client.email().await?;
+        let registration = self.product_client.get_registration().await?;
 
         Ok(ProductSettings {
             id: Some(product),
-            registration_code: Some(registration_code),
-            registration_email: Some(email),
+            registration_code: Some(registration.key),
+            registration_email: Some(registration.email),
         })
     }
 
@@ -41,7 +40,12 @@ impl<'a> ProductStore<'a> {
                 probe = true;
             }
         }
-        if let Some(reg_code) = &settings.registration_code {
+        // an empty code means that the product is not registered
+        if let Some(reg_code) = settings
+            .registration_code
+            .as_ref()
+            .filter(|c| !c.is_empty())
+        {
             let (result, message);
             if let Some(email) = &settings.registration_email {
                 (result, message) = self.product_client.register(reg_code, email).await?;
@@ -49,7 +53,6 @@ impl<'a> ProductStore<'a> {
                 (result, message) = self.product_client.register(reg_code, "").await?;
             }
             // FIXME: name the magic numbers. 3 is Registration not required
-            // FIXME: well don't register when not required (no regcode in profile)
             if result != 0 && result != 3 {
                 return Err(ServiceError::FailedRegistration(message));
             }
@@ -63,3 +66,156 @@ impl<'a> ProductStore<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base_http_client::BaseHTTPClient;
+    use httpmock::prelude::*;
+    use std::error::Error;
+    use tokio::test; // without this, "error: async functions cannot be used for tests"
+
+    fn product_store(mock_server_url: String) -> ProductStore {
+        let mut bhc = BaseHTTPClient::default();
+        bhc.base_url = mock_server_url;
+        ProductStore::new(bhc).unwrap()
+    }
+
+    #[test]
+    async fn test_getting_product() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let software_mock = server.mock(|when, then| {
+            when.method(GET).path("/api/software/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "patterns": {"xfce":true},
+                    "product": "Tumbleweed"
+                }"#,
+                );
+        });
+        let registration_mock = server.mock(|when, then| {
+            when.method(GET).path("/api/software/registration");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "key": "",
+                    "email": "",
+                    "requirement": "NotRequired"
+                }"#,
+                );
+        });
+        let url = server.url("/api");
+
+        let store = product_store(url);
+        let settings = store.load().await?;
+
+        let expected = ProductSettings {
+            id: Some("Tumbleweed".to_owned()),
+            registration_code: Some("".to_owned()),
+            registration_email: Some("".to_owned()),
+        };
+        // main assertion
+        assert_eq!(settings, expected);
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        software_mock.assert();
+        registration_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_setting_product_ok() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        // no product selected at first
+        let get_software_mock = server.mock(|when, then| {
+            when.method(GET).path("/api/software/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "patterns": {},
+                    "product": ""
+                }"#,
+                );
+        });
+        let software_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .header("content-type", "application/json")
+                .body(r#"{"patterns":null,"product":"Tumbleweed"}"#);
+            then.status(200);
+        });
+        let manager_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/manager/probe")
+                .header("content-type", "application/json")
+                .body("null");
+            then.status(200);
+        });
+        let url = server.url("/api");
+
+        let store = product_store(url);
+        let settings = ProductSettings {
+            id: Some("Tumbleweed".to_owned()),
+            registration_code: None,
+            registration_email: None,
+        };
+
+        let result = store.store(&settings).await;
+
+        // main assertion
+        result?;
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        get_software_mock.assert();
+        software_mock.assert();
+        manager_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_setting_product_registration_failed() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let get_software_mock = server.mock(|when, then| {
+            when.method(GET).path("/api/software/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "patterns": {},
+                    "product": "SLES"
+                }"#,
+                );
+        });
+        let registration_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/software/registration")
+                .header("content-type", "application/json")
+                .body(r#"{"key":"1234","email":""}"#);
+            then.status(422)
+                .header("content-type", "application/json")
+                .body(r#"{"id": 1, "message": "Invalid registration code"}"#);
+        });
+        let url = server.url("/api");
+
+        let store = product_store(url);
+        let settings = ProductSettings {
+            id: Some("SLES".to_owned()),
+            registration_code: Some("1234".to_owned()),
+            registration_email: None,
+        };
+
+        let result = store.store(&settings).await;
+
+        // main assertion
+        assert!(matches!(result, Err(ServiceError::FailedRegistration(_))));
+
+        // Ensure the specified mock was called exactly one time (or fail with a detailed error description).
+        get_software_mock.assert();
+        registration_mock.assert();
+        Ok(())
+    }
+}
@@ -46,14 +46,19 @@
 //!}
 //! ```
 
-use crate::{error::ServiceError, proxies::ProgressProxy};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    error::ServiceError,
+    events::{Event, EventsClient, MANAGER_SERVICE, SOFTWARE_SERVICE},
+    proxies::ProgressProxy,
+};
 use async_trait::async_trait;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio_stream::{StreamExt, StreamMap};
 use zbus::Connection;
 
 /// Represents the progress for an Agama service.
-#[derive(Clone, Default, Debug, Serialize)]
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Progress {
     /// Current step
@@ -177,6 +182,110 @@ impl<'a> ProgressMonitor<'a> {
     }
 }
 
+/// Monitorizes and reports the progress of Agama's current operation through the HTTP API.
+///
+/// It offers the same main/details reporting than [ProgressMonitor] but, as it does not rely on
+/// D-Bus, it can be used against a remote server. It listens to the progress events sent through
+/// the WebSocket.
+///
+/// Connect the monitor before starting the operation to not miss any event.
+pub struct WebSocketProgressMonitor {
+    client: BaseHTTPClient,
+    events: EventsClient,
+}
+
+impl WebSocketProgressMonitor {
+    const MAIN_PATH: &'static str = "/manager/progress";
+
+    /// Connects to the events of the server.
+    ///
+    /// * `client`: authenticated HTTP client.
+    pub async fn connect(client: BaseHTTPClient) -> Result<Self, ServiceError> {
+        let events = EventsClient::connect(&client).await?;
+        Ok(Self { client, events })
+    }
+
+    /// Runs the monitor until the operation started by the caller finishes.
+    ///
+    /// The server may still report the previous operation as finished right after starting a
+    /// new one. Hence, it waits for the operation to start (the manager reports some progress or
+    /// becomes busy) before considering that it finished.
+    ///
+    /// * `presenter`: progress presenter.
+    pub async fn run(self, presenter: impl ProgressPresenter) -> Result<(), ServiceError> {
+        self.monitor(presenter, false).await
+    }
+
+    /// Runs the monitor until the manager is idle.
+    ///
+    /// Unlike [WebSocketProgressMonitor::run], it returns immediately if there is no operation
+    /// in progress.
+    ///
+    /// * `presenter`: progress presenter.
+    pub async fn wait(self, presenter: impl ProgressPresenter) -> Result<(), ServiceError> {
+        self.monitor(presenter, true).await
+    }
+
+    /// Reports the progress until the operation finishes.
+    ///
+    /// * `presenter`: progress presenter.
+    /// * `idle`: whether it is enough to find the manager idle (no operation to wait for).
+    async fn monitor(
+        mut self,
+        mut presenter: impl ProgressPresenter,
+        idle: bool,
+    ) -> Result<(), ServiceError> {
+        let main: Progress = self.client.get(Self::MAIN_PATH).await?;
+        if main.finished && idle {
+            return Ok(());
+        }
+
+        let mut started = !main.finished;
+        let mut presenting = started;
+        if presenting {
+            presenter.start(&main).await;
+        }
+
+        while let Some(event) = self.events.receive().await? {
+            let finished = match event {
+                Event::Progress { service, progress } if service == MANAGER_SERVICE => {
+                    if !progress.finished {
+                        started = true;
+                        if presenting {
+                            presenter.update_main(&progress).await;
+                        } else {
+                            presenting = true;
+                            presenter.start(&progress).await;
+                        }
+                    }
+                    progress.finished
+                }
+                Event::Progress { service, progress } if service == SOFTWARE_SERVICE => {
+                    if presenting {
+                        presenter.update_detail(&progress).await;
+                    }
+                    false
+                }
+                Event::ServiceStatusChanged { service, status } if service == MANAGER_SERVICE => {
+                    let idle = status == 0;
+                    started |= !idle;
+                    idle
+                }
+                _ => false,
+            };
+
+            if finished && started {
+                break;
+            }
+        }
+
+        if presenting {
+            presenter.finish().await;
+        }
+        Ok(())
+    }
+}
+
 /// Presents the progress to the user.
 #[async_trait]
 pub trait ProgressPresenter {
@@ -198,3 +307,131 @@ pub trait ProgressPresenter {
     /// Finishes the progress reporting.
     async fn finish(&mut self);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, ProgressPresenter, WebSocketProgressMonitor};
+    use crate::{auth::AuthToken, base_http_client::BaseHTTPClient};
+    use async_trait::async_trait;
+    use futures_util::SinkExt;
+    use std::sync::{Arc, Mutex};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+    use tokio_tungstenite::tungstenite::Message;
+
+    // Records the calls in a shared list.
+    #[derive(Clone, Default)]
+    struct TestPresenter {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TestPresenter {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[async_trait]
+    impl ProgressPresenter for TestPresenter {
+        async fn start(&mut self, progress: &Progress) {
+            self.record(format!("start {}", progress.current_step));
+        }
+
+        async fn update_main(&mut self, progress: &Progress) {
+            self.record(format!("main {}", progress.current_step));
+        }
+
+        async fn update_detail(&mut self, progress: &Progress) {
+            self.record(format!("detail {}", progress.current_step));
+        }
+
+        async fn finish(&mut self) {
+            self.record("finish".to_string());
+        }
+    }
+
+    fn progress_event(service: &str, step: u32, finished: bool) -> String {
+        serde_json::json!({
+            "type": "Progress", "service": service, "currentStep": step, "maxSteps": 2,
+            "currentTitle": "", "finished": finished
+        })
+        .to_string()
+    }
+
+    // Serves the WebSocket (with the given events) and the manager progress, which is finished.
+    async fn start_server(events: Vec<String>) -> (BaseHTTPClient, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            assert!(request.starts_with(b"GET /api/manager/progress "));
+            let body = r#"{"currentStep":0,"maxSteps":0,"currentTitle":"","finished":true}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+
+            for event in events {
+                socket.send(Message::Text(event)).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+        });
+
+        let client = BaseHTTPClient::default()
+            .with_base_url(&format!("http://{address}/api"))
+            .with_token(&AuthToken::new("123456"))
+            .unwrap();
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn test_run_waits_for_the_operation() {
+        let manager = "org.opensuse.Agama.Manager1";
+        let events = vec![
+            // the previous operation
+            progress_event(manager, 0, true),
+            r#"{"type":"ServiceStatusChanged","service":"org.opensuse.Agama.Manager1","status":1}"#
+                .to_string(),
+            progress_event(manager, 1, false),
+            progress_event("org.opensuse.Agama.Software1", 1, false),
+            progress_event(manager, 2, true),
+        ];
+        let (client, server) = start_server(events).await;
+
+        let monitor = WebSocketProgressMonitor::connect(client).await.unwrap();
+        let presenter = TestPresenter::default();
+        monitor.run(presenter.clone()).await.unwrap();
+        assert_eq!(presenter.calls(), vec!["start 1", "detail 1", "finish"]);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_when_idle() {
+        let (client, server) = start_server(vec![]).await;
+
+        let monitor = WebSocketProgressMonitor::connect(client).await.unwrap();
+        let presenter = TestPresenter::default();
+        monitor.wait(presenter.clone()).await.unwrap();
+        assert!(presenter.calls().is_empty());
+        server.await.unwrap();
+    }
+}
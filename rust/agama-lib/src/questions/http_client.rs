@@ -5,7 +5,7 @@ use tokio::time::sleep;
 
 use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
 
-use super::model::{self, Answer, Question};
+use super::model::{self, Answer, Question, QuestionsMode};
 
 pub struct HTTPClient {
     client: BaseHTTPClient,
@@ -18,6 +18,10 @@ impl HTTPClient {
         })
     }
 
+    pub fn new_with_base(base: BaseHTTPClient) -> Self {
+        Self { client: base }
+    }
+
     pub async fn list_questions(&self) -> Result<Vec<model::Question>, ServiceError> {
         self.client.get("/questions").await
     }
@@ -62,6 +66,18 @@ impl HTTPClient {
         let path = format!("/questions/{}", question_id);
         self.client.delete_void(path.as_str()).await
     }
+
+    /// Sets whether the questions are asked to the user or answered automatically.
+    pub async fn set_mode(&self, interactive: bool) -> Result<(), ServiceError> {
+        self.client
+            .put_void("/questions/mode", &QuestionsMode { interactive })
+            .await
+    }
+
+    /// Adds predefined answers (see the answers file format in the questions documentation).
+    pub async fn add_answers(&self, answers: &serde_json::Value) -> Result<(), ServiceError> {
+        self.client.post_void("/questions/answers", answers).await
+    }
 }
 
 #[cfg(test)]
@@ -174,6 +190,35 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    async fn test_set_mode_and_answers() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let client = questions_client(server.url("/api"));
+
+        let mode_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/questions/mode")
+                .body(r#"{"interactive":false}"#);
+            then.status(200);
+        });
+        let answers_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/questions/answers")
+                .body(r#"{"answers":[{"answer":"yes","class":"storage.activate_multipath"}]}"#);
+            then.status(200);
+        });
+
+        client.set_mode(false).await?;
+        let answers = serde_json::json!({
+            "answers": [{ "class": "storage.activate_multipath", "answer": "yes" }]
+        });
+        client.add_answers(&answers).await?;
+
+        mode_mock.assert();
+        answers_mock.assert();
+        Ok(())
+    }
+
     #[test]
     async fn test_try_answer() -> Result<(), Box<dyn Error>> {
         let server = MockServer::start();
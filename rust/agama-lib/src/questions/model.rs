@@ -62,3 +62,12 @@ pub struct GenericAnswer {
 pub struct PasswordAnswer {
     pub password: String,
 }
+
+/// How the questions are answered.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionsMode {
+    /// Whether the questions are asked to the user (true) or answered with the default option
+    /// (false).
+    pub interactive: bool,
+}
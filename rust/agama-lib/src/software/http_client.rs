@@ -55,7 +55,6 @@ impl SoftwareHTTPClient {
     ) -> Result<(), ServiceError> {
         let config = SoftwareConfig {
             product: None,
-            // TODO: SoftwareStore only passes true bools, false branch is untested
             patterns: Some(patterns),
         };
         self.set_config(&config).await
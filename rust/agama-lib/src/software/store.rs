@@ -3,17 +3,18 @@
 use std::collections::HashMap;
 
 use super::{SoftwareHTTPClient, SoftwareSettings};
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
 
-/// Loads and stores the software settings from/to the D-Bus service.
+/// Loads and stores the software settings from/to the HTTP API.
 pub struct SoftwareStore {
     software_client: SoftwareHTTPClient,
 }
 
 impl SoftwareStore {
-    pub fn new() -> Result<SoftwareStore, ServiceError> {
+    pub fn new(client: BaseHTTPClient) -> Result<SoftwareStore, ServiceError> {
         Ok(Self {
-            software_client: SoftwareHTTPClient::new()?,
+            software_client: SoftwareHTTPClient::new_with_base(client),
         })
     }
 
@@ -32,6 +33,28 @@ impl SoftwareStore {
 
         Ok(())
     }
+
+    /// Restores the previous software settings.
+    ///
+    /// The patterns that were selected when storing `applied` are explicitly deselected.
+    ///
+    /// * `previous`: settings to restore.
+    /// * `applied`: settings that were stored.
+    pub async fn restore(
+        &self,
+        previous: &SoftwareSettings,
+        applied: &SoftwareSettings,
+    ) -> Result<(), ServiceError> {
+        let mut patterns: HashMap<String, bool> = applied
+            .patterns
+            .iter()
+            .map(|name| (name.to_owned(), false))
+            .collect();
+        patterns.extend(previous.patterns.iter().map(|name| (name.to_owned(), true)));
+        self.software_client.select_patterns(patterns).await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
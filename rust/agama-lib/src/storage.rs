@@ -1,6 +1,7 @@
 //! Implements support for handling the storage settings
 
 pub mod client;
+mod http_client;
 pub mod model;
 pub mod proxies;
 mod settings;
@@ -10,5 +11,6 @@ pub use client::{
     iscsi::{ISCSIAuth, ISCSIClient, ISCSIInitiator, ISCSINode},
     StorageClient,
 };
+pub use http_client::StorageHTTPClient;
 pub use settings::StorageSettings;
 pub use store::StorageStore;
@@ -0,0 +1,30 @@
+//! Implements a client to access Agama's storage service.
+use crate::base_http_client::BaseHTTPClient;
+use crate::error::ServiceError;
+use crate::storage::StorageSettings;
+
+pub struct StorageHTTPClient {
+    client: BaseHTTPClient,
+}
+
+impl StorageHTTPClient {
+    pub fn new() -> Result<Self, ServiceError> {
+        Ok(Self {
+            client: BaseHTTPClient::new()?,
+        })
+    }
+
+    pub fn new_with_base(base: BaseHTTPClient) -> Self {
+        Self { client: base }
+    }
+
+    /// Get the storage config according to the JSON schema
+    pub async fn get_config(&self) -> Result<StorageSettings, ServiceError> {
+        self.client.get("/storage/config").await
+    }
+
+    /// Set the storage config according to the JSON schema
+    pub async fn set_config(&self, config: &StorageSettings) -> Result<(), ServiceError> {
+        self.client.put_void("/storage/config", config).await
+    }
+}
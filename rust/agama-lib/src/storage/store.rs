@@ -1,27 +1,26 @@
 //! Implements the store for the storage settings.
 
-use super::{StorageClient, StorageSettings};
+use super::{StorageHTTPClient, StorageSettings};
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
-use zbus::Connection;
 
-/// Loads and stores the storage settings from/to the D-Bus service.
-pub struct StorageStore<'a> {
-    storage_client: StorageClient<'a>,
+/// Loads and stores the storage settings from/to the HTTP API.
+pub struct StorageStore {
+    storage_client: StorageHTTPClient,
 }
 
-impl<'a> StorageStore<'a> {
-    pub async fn new(connection: Connection) -> Result<StorageStore<'a>, ServiceError> {
+impl StorageStore {
+    pub fn new(client: BaseHTTPClient) -> Result<StorageStore, ServiceError> {
         Ok(Self {
-            storage_client: StorageClient::new(connection).await?,
+            storage_client: StorageHTTPClient::new_with_base(client),
         })
     }
 
     pub async fn load(&self) -> Result<StorageSettings, ServiceError> {
-        Ok(self.storage_client.get_config().await?)
+        self.storage_client.get_config().await
     }
 
     pub async fn store(&self, settings: StorageSettings) -> Result<(), ServiceError> {
-        self.storage_client.set_config(settings).await?;
-        Ok(())
+        self.storage_client.set_config(&settings).await
     }
 }
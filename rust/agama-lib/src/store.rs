@@ -1,84 +1,538 @@
-//! Load/store the settings from/to the D-Bus services.
+//! Load/store the settings from/to the HTTP API.
 // TODO: quickly explain difference between FooSettings and FooStore, with an example
 
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
 use crate::install_settings::InstallSettings;
 use crate::{
     localization::LocalizationStore, network::NetworkStore, product::ProductStore,
     software::SoftwareStore, storage::StorageStore, users::UsersStore,
 };
-use zbus::Connection;
 
-/// Struct that loads/stores the settings from/to the D-Bus services.
+/// Struct that loads/stores the settings from/to the HTTP API.
 ///
 /// It is composed by a set of "stores" that are able to load/store the
 /// settings for each service.
 ///
-/// This struct uses the default connection built by [connection function](super::connection).
-pub struct Store<'a> {
+/// All the stores share the given [BaseHTTPClient], so they talk to the same (local or remote)
+/// server.
+pub struct Store {
     users: UsersStore,
     network: NetworkStore,
-    product: ProductStore<'a>,
+    product: ProductStore,
     software: SoftwareStore,
-    storage: StorageStore<'a>,
+    storage: StorageStore,
     localization: LocalizationStore,
 }
 
-impl<'a> Store<'a> {
-    pub async fn new(
-        connection: Connection,
-        http_client: reqwest::Client,
-    ) -> Result<Store<'a>, ServiceError> {
+impl Store {
+    pub async fn new(http_client: BaseHTTPClient) -> Result<Store, ServiceError> {
         Ok(Self {
-            localization: LocalizationStore::new()?,
-            users: UsersStore::new()?,
-            network: NetworkStore::new(http_client).await?,
-            product: ProductStore::new(connection.clone()).await?,
-            software: SoftwareStore::new()?,
-            storage: StorageStore::new(connection).await?,
+            localization: LocalizationStore::new(http_client.clone())?,
+            users: UsersStore::new(http_client.clone())?,
+            network: NetworkStore::new(http_client.clone()).await?,
+            product: ProductStore::new(http_client.clone())?,
+            software: SoftwareStore::new(http_client.clone())?,
+            storage: StorageStore::new(http_client)?,
         })
     }
 
     /// Loads the installation settings from the HTTP interface.
     pub async fn load(&self) -> Result<InstallSettings, ServiceError> {
-        let mut settings: InstallSettings = Default::default();
-        settings.network = Some(self.network.load().await?);
-        settings.software = Some(self.software.load().await?);
-        settings.user = Some(self.users.load().await?);
-        settings.product = Some(self.product.load().await?);
-        settings.localization = Some(self.localization.load().await?);
+        self.load_sections(&Section::ALL).await
+    }
+
+    /// Stores the given installation settings through the HTTP API
+    pub async fn store(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        for section in Section::included_in(settings) {
+            self.store_section(section, settings).await?;
+        }
+        Ok(())
+    }
+
+    /// Stores the given installation settings in an all-or-nothing fashion.
+    ///
+    /// Before applying the settings, it takes a snapshot of each section to change. If applying
+    /// a section fails, it restores the snapshot of the sections that were already applied
+    /// (including the failing one) and reports which section failed. If restoring a section
+    /// fails, it goes on with the rest of them and reports the first error.
+    pub async fn store_atomic(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
+        let sections = Section::included_in(settings);
+        let snapshot = self.load_sections(&sections).await?;
 
-        let storage_settings = self.storage.load().await?;
-        settings.storage = storage_settings.storage.clone();
-        settings.storage_autoyast = storage_settings.storage_autoyast.clone();
+        for (index, section) in sections.iter().enumerate() {
+            let Err(error) = self.store_section(*section, settings).await else {
+                continue;
+            };
+
+            log::warn!("Could not store the {section} settings: {error}. Rolling back.");
+            let mut first_rollback_error = None;
+            for applied in sections[..=index].iter().rev() {
+                if let Err(rollback_error) =
+                    self.restore_section(*applied, &snapshot, settings).await
+                {
+                    log::error!("Could not restore the {applied} settings: {rollback_error}");
+                    first_rollback_error.get_or_insert(rollback_error);
+                }
+            }
+            return match first_rollback_error {
+                Some(rollback_error) => Err(ServiceError::FailedRollback(
+                    section.to_string(),
+                    Box::new(error),
+                    Box::new(rollback_error),
+                )),
+                None => Err(ServiceError::FailedSection(
+                    section.to_string(),
+                    Box::new(error),
+                )),
+            };
+        }
+        Ok(())
+    }
+
+    /// Loads the settings of the given sections.
+    ///
+    /// The rest of the sections are left empty.
+    ///
+    /// * `sections`: sections to load.
+    pub async fn load_sections(
+        &self,
+        sections: &[Section],
+    ) -> Result<InstallSettings, ServiceError> {
+        let mut settings = InstallSettings::default();
+
+        for section in sections {
+            match section {
+                Section::Network => settings.network = Some(self.network.load().await?),
+                Section::Product => settings.product = Some(self.product.load().await?),
+                Section::Localization => {
+                    settings.localization = Some(self.localization.load().await?)
+                }
+                Section::Software => settings.software = Some(self.software.load().await?),
+                Section::Users => settings.user = Some(self.users.load().await?),
+                Section::Storage => {
+                    let storage_settings = self.storage.load().await?;
+                    settings.storage = storage_settings.storage;
+                    settings.storage_autoyast = storage_settings.storage_autoyast;
+                }
+            }
+        }
 
         // TODO: use try_join here
         Ok(settings)
     }
 
-    /// Stores the given installation settings in the D-Bus service
-    pub async fn store(&self, settings: &InstallSettings) -> Result<(), ServiceError> {
-        if let Some(network) = &settings.network {
-            self.network.store(network).await?;
+    /// Stores one section of the given installation settings.
+    ///
+    /// * `section`: section to store.
+    /// * `settings`: installation settings.
+    async fn store_section(
+        &self,
+        section: Section,
+        settings: &InstallSettings,
+    ) -> Result<(), ServiceError> {
+        match section {
+            Section::Network => {
+                if let Some(network) = &settings.network {
+                    self.network.store(network).await?;
+                }
+            }
+            Section::Product => {
+                if let Some(product) = &settings.product {
+                    self.product.store(product).await?;
+                }
+            }
+            Section::Localization => {
+                if let Some(localization) = &settings.localization {
+                    self.localization.store(localization).await?;
+                }
+            }
+            Section::Software => {
+                if let Some(software) = &settings.software {
+                    self.software.store(software).await?;
+                }
+            }
+            Section::Users => {
+                if let Some(user) = &settings.user {
+                    self.users.store(user).await?;
+                }
+            }
+            Section::Storage => {
+                if settings.storage.is_some() || settings.storage_autoyast.is_some() {
+                    self.storage.store(settings.into()).await?
+                }
+            }
         }
-        // order is important here as network can be critical for connection
-        // to registration server and selecting product is important for rest
-        if let Some(product) = &settings.product {
-            self.product.store(product).await?;
+        Ok(())
+    }
+
+    /// Restores one section to the previous settings.
+    ///
+    /// Storing a section only adds or updates settings, so the elements introduced by the applied
+    /// settings (e.g., patterns, network connections or root credentials) are explicitly removed.
+    ///
+    /// * `section`: section to restore.
+    /// * `previous`: settings to restore.
+    /// * `applied`: settings that were stored.
+    async fn restore_section(
+        &self,
+        section: Section,
+        previous: &InstallSettings,
+        applied: &InstallSettings,
+    ) -> Result<(), ServiceError> {
+        match section {
+            Section::Network => {
+                if let (Some(previous), Some(applied)) = (&previous.network, &applied.network) {
+                    self.network.restore(previous, applied).await?;
+                }
+            }
+            Section::Software => {
+                if let (Some(previous), Some(applied)) = (&previous.software, &applied.software) {
+                    self.software.restore(previous, applied).await?;
+                }
+            }
+            Section::Users => {
+                if let (Some(previous), Some(applied)) = (&previous.user, &applied.user) {
+                    self.users.restore(previous, applied).await?;
+                }
+            }
+            _ => self.store_section(section, previous).await?,
         }
-        // ordering: localization after product as some product may miss some locales
-        if let Some(localization) = &settings.localization {
-            self.localization.store(localization).await?;
+        Ok(())
+    }
+}
+
+/// Sections of the installation settings, as handled by the [Store].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Section {
+    Network,
+    Product,
+    Localization,
+    Software,
+    Users,
+    Storage,
+}
+
+impl Section {
+    /// All the sections, in the order they must be stored.
+    pub const ALL: [Section; 6] = [
+        Self::Network,
+        Self::Product,
+        Self::Localization,
+        Self::Software,
+        Self::Users,
+        Self::Storage,
+    ];
+
+    /// Returns the sections included in the given settings, in the order they must be stored.
+    ///
+    /// The order is important: network can be critical for connecting to the registration server
+    /// and selecting the product is important for the rest. Additionally, localization goes after
+    /// the product because some products may miss some locales.
+    ///
+    /// * `settings`: installation settings.
+    fn included_in(settings: &InstallSettings) -> Vec<Section> {
+        let mut sections = vec![];
+        if settings.network.is_some() {
+            sections.push(Self::Network);
+        }
+        if settings.product.is_some() {
+            sections.push(Self::Product);
         }
-        if let Some(software) = &settings.software {
-            self.software.store(software).await?;
+        if settings.localization.is_some() {
+            sections.push(Self::Localization);
         }
-        if let Some(user) = &settings.user {
-            self.users.store(user).await?;
+        if settings.software.is_some() {
+            sections.push(Self::Software);
+        }
+        if settings.user.is_some() {
+            sections.push(Self::Users);
         }
         if settings.storage.is_some() || settings.storage_autoyast.is_some() {
-            self.storage.store(settings.into()).await?
+            sections.push(Self::Storage);
+        }
+        sections
+    }
+
+    /// Removes the settings of this section from the given installation settings.
+    ///
+    /// * `settings`: installation settings.
+    pub fn clear(&self, settings: &mut InstallSettings) {
+        match self {
+            Self::Network => settings.network = None,
+            Self::Product => settings.product = None,
+            Self::Localization => settings.localization = None,
+            Self::Software => settings.software = None,
+            Self::Users => settings.user = None,
+            Self::Storage => {
+                settings.storage = None;
+                settings.storage_autoyast = None;
+            }
+        }
+    }
+
+    /// Keeps only the settings of the given sections.
+    ///
+    /// * `settings`: installation settings.
+    /// * `sections`: sections to keep.
+    pub fn retain(settings: &mut InstallSettings, sections: &[Section]) {
+        for section in Self::ALL.iter().filter(|s| !sections.contains(s)) {
+            section.clear(settings);
+        }
+    }
+}
+
+impl std::str::FromStr for Section {
+    type Err = ServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|section| section.to_string() == s)
+            .ok_or_else(|| ServiceError::UnknownSection(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Network => "network",
+            Self::Product => "product",
+            Self::Localization => "localization",
+            Self::Software => "software",
+            Self::Users => "users",
+            Self::Storage => "storage",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        localization::LocalizationSettings, product::ProductSettings, software::SoftwareSettings,
+    };
+    use httpmock::prelude::*;
+    use httpmock::Method::PATCH;
+    use std::error::Error;
+    use tokio::test; // without this, "error: async functions cannot be used for tests"
+
+    async fn store(mock_server_url: String) -> Result<Store, ServiceError> {
+        let mut bhc = BaseHTTPClient::default();
+        bhc.base_url = mock_server_url;
+        Store::new(bhc).await
+    }
+
+    fn settings() -> InstallSettings {
+        InstallSettings {
+            user: None,
+            localization: Some(LocalizationSettings {
+                language: Some("fr_FR.UTF-8".to_owned()),
+                keyboard: Some("fr(dvorak)".to_owned()),
+                timezone: Some("Europe/Paris".to_owned()),
+            }),
+            software: Some(SoftwareSettings {
+                patterns: vec!["xfce".to_owned()],
+            }),
+            ..Default::default()
         }
+    }
+
+    fn mock_loading(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method(GET).path("/api/l10n/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                    "locales": ["en_US.UTF-8"],
+                    "keymap": "us",
+                    "timezone": "Europe/Berlin",
+                    "uiLocale": "en_US.UTF-8",
+                    "uiKeymap": "us"
+                }"#,
+                );
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/api/software/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"patterns": {}, "product": "Tumbleweed"}"#);
+        });
+    }
+
+    #[test]
+    async fn test_load_sections() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        mock_loading(&server);
+
+        let store = store(server.url("/api")).await?;
+        let settings = store.load_sections(&[Section::Localization]).await?;
+
+        let localization = settings.localization.unwrap();
+        assert_eq!(localization.language, Some("en_US.UTF-8".to_string()));
+        assert!(settings.software.is_none());
+        assert!(settings.network.is_none());
+        Ok(())
+    }
+
+    #[test]
+    async fn test_retain_sections() -> Result<(), Box<dyn Error>> {
+        let mut settings = settings();
+        let sections: Vec<Section> = vec!["software".parse()?];
+        Section::retain(&mut settings, &sections);
+
+        assert!(settings.localization.is_none());
+        assert!(settings.software.is_some());
+        assert!("unknown".parse::<Section>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    async fn test_store_atomic_ok() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        mock_loading(&server);
+        let l10n_mock = server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/l10n/config")
+                .body(
+                    r#"{"locales":["fr_FR.UTF-8"],"keymap":"fr(dvorak)","timezone":"Europe/Paris","uiLocale":null,"uiKeymap":null}"#
+                );
+            then.status(204);
+        });
+        let software_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":{"xfce":true},"product":null}"#);
+            then.status(200);
+        });
+
+        let store = store(server.url("/api")).await?;
+        store.store_atomic(&settings()).await?;
+
+        l10n_mock.assert();
+        software_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_store_atomic_rollback() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        mock_loading(&server);
+        let l10n_mock = server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/l10n/config")
+                .body(
+                    r#"{"locales":["fr_FR.UTF-8"],"keymap":"fr(dvorak)","timezone":"Europe/Paris","uiLocale":null,"uiKeymap":null}"#
+                );
+            then.status(204);
+        });
+        let software_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":{"xfce":true},"product":null}"#);
+            then.status(400)
+                .header("content-type", "application/json")
+                .body(r#"{"error": "Failed to find these patterns: [\"xfce\"]"}"#);
+        });
+        let l10n_restore_mock = server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/l10n/config")
+                .body(
+                    r#"{"locales":["en_US.UTF-8"],"keymap":"us","timezone":"Europe/Berlin","uiLocale":null,"uiKeymap":null}"#
+                );
+            then.status(204);
+        });
+        let software_restore_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":{"xfce":false},"product":null}"#);
+            then.status(200);
+        });
+
+        let store = store(server.url("/api")).await?;
+        let result = store.store_atomic(&settings()).await;
+
+        // main assertion
+        let Err(ServiceError::FailedSection(section, _)) = result else {
+            panic!("Unexpected result: {:?}", result);
+        };
+        assert_eq!(section, "software");
+
+        l10n_mock.assert();
+        software_mock.assert();
+        software_restore_mock.assert();
+        l10n_restore_mock.assert();
+        Ok(())
+    }
+
+    #[test]
+    async fn test_store_atomic_rollback_error() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        mock_loading(&server);
+        server.mock(|when, then| {
+            when.method(GET).path("/api/software/registration");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"key": "", "email": "", "requirement": "NotRequired"}"#);
+        });
+        let product_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":null,"product":"SLES"}"#);
+            then.status(200);
+        });
+        let probe_mock = server.mock(|when, then| {
+            when.method(POST).path("/api/manager/probe");
+            then.status(200);
+        });
+        let registration_mock = server.mock(|when, then| {
+            when.method(POST).path("/api/software/registration");
+            then.status(200);
+        });
+        let l10n_mock = server.mock(|when, then| {
+            when.method(PATCH).path("/api/l10n/config");
+            then.status(204);
+        });
+        let software_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":{"xfce":true},"product":null}"#);
+            then.status(400)
+                .header("content-type", "application/json")
+                .body(r#"{"error": "Failed to find these patterns: [\"xfce\"]"}"#);
+        });
+        let software_restore_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/software/config")
+                .body(r#"{"patterns":{"xfce":false},"product":null}"#);
+            then.status(500);
+        });
+
+        let mut settings = settings();
+        settings.product = Some(ProductSettings {
+            id: Some("SLES".to_owned()),
+            registration_code: None,
+            registration_email: None,
+        });
+        let store = store(server.url("/api")).await?;
+        let result = store.store_atomic(&settings).await;
+
+        // main assertion
+        let Err(ServiceError::FailedRollback(section, _, _)) = result else {
+            panic!("Unexpected result: {:?}", result);
+        };
+        assert_eq!(section, "software");
+
+        product_mock.assert();
+        probe_mock.assert();
+        software_mock.assert();
+        software_restore_mock.assert();
+        // the rest of the sections are restored anyway
+        l10n_mock.assert_hits(2);
+        // the product was not registered, so the registration is skipped
+        registration_mock.assert_hits(0);
         Ok(())
     }
 }
@@ -32,7 +32,8 @@ impl UsersHTTPClient {
         result
     }
 
-    async fn root_config(&self) -> Result<RootConfig, ServiceError> {
+    /// Returns the configuration of the root user
+    pub async fn root_config(&self) -> Result<RootConfig, ServiceError> {
         self.client.get("/users/root").await
     }
 
@@ -1,15 +1,16 @@
 use super::{FirstUser, FirstUserSettings, RootUserSettings, UserSettings, UsersHTTPClient};
+use crate::base_http_client::BaseHTTPClient;
 use crate::error::ServiceError;
 
-/// Loads and stores the users settings from/to the D-Bus service.
+/// Loads and stores the users settings from/to the HTTP API.
 pub struct UsersStore {
     users_client: UsersHTTPClient,
 }
 
 impl UsersStore {
-    pub fn new() -> Result<Self, ServiceError> {
+    pub fn new(client: BaseHTTPClient) -> Result<Self, ServiceError> {
         Ok(Self {
-            users_client: UsersHTTPClient::new()?,
+            users_client: UsersHTTPClient::new_with_base(client)?,
         })
     }
 
@@ -27,10 +28,14 @@ impl UsersStore {
             full_name: Some(first_user.full_name),
             password: Some(first_user.password),
         };
+        let root_config = self.users_client.root_config().await?;
         let mut root_user = RootUserSettings::default();
-        let ssh_public_key = self.users_client.root_ssh_key().await?;
-        if !ssh_public_key.is_empty() {
-            root_user.ssh_public_key = Some(ssh_public_key)
+        // the password cannot be read back, but an empty one means that it is not set
+        if !root_config.password {
+            root_user.password = Some("".to_string());
+        }
+        if !root_config.sshkey.is_empty() {
+            root_user.ssh_public_key = Some(root_config.sshkey)
         }
         Ok(UserSettings {
             first_user: Some(first_user),
@@ -50,6 +55,43 @@ impl UsersStore {
         Ok(())
     }
 
+    /// Restores the previous users settings.
+    ///
+    /// The root credentials set when storing `applied` are removed if they were not set before.
+    /// As the root password cannot be read back, it fails if `applied` replaced an existing one.
+    ///
+    /// * `previous`: settings to restore.
+    /// * `applied`: settings that were stored.
+    pub async fn restore(
+        &self,
+        previous: &UserSettings,
+        applied: &UserSettings,
+    ) -> Result<(), ServiceError> {
+        if let Some(first_user) = &previous.first_user {
+            self.store_first_user(first_user).await?;
+        }
+
+        let Some(applied) = &applied.root else {
+            return Ok(());
+        };
+        let previous = previous.root.as_ref();
+        if applied.ssh_public_key.is_some() {
+            let ssh_public_key = previous
+                .and_then(|r| r.ssh_public_key.as_deref())
+                .unwrap_or_default();
+            self.users_client.set_root_sshkey(ssh_public_key).await?;
+        }
+        if applied.password.is_some() {
+            match previous.and_then(|r| r.password.as_ref()) {
+                Some(password) if password.is_empty() => {
+                    self.users_client.set_root_password("", false).await?;
+                }
+                _ => return Err(ServiceError::CannotRestoreRootPassword),
+            }
+        }
+        Ok(())
+    }
+
     async fn store_first_user(&self, settings: &FirstUserSettings) -> Result<(), ServiceError> {
         let first_user = FirstUser {
             user_name: settings.user_name.clone().unwrap_or_default(),
@@ -133,7 +175,7 @@ mod test {
             autologin: Some(true),
         };
         let root_user = RootUserSettings {
-            // FIXME this is weird: no matter what HTTP reports, we end up with None
+            // the password is set, but it cannot be read back
             password: None,
             ssh_public_key: Some("keykeykey".to_owned()),
         };
@@ -208,4 +250,58 @@ mod test {
         root_mock2.assert();
         Ok(())
     }
+
+    #[test]
+    async fn test_restore_users() -> Result<(), Box<dyn Error>> {
+        let server = MockServer::start();
+        let user_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/users/first")
+                .body(r#"{"fullName":"","userName":"","password":"","autologin":false,"data":{}}"#);
+            then.status(200);
+        });
+        let sshkey_mock = server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/users/root")
+                .body(r#"{"sshkey":"","password":null,"passwordEncrypted":null}"#);
+            then.status(200).body("0");
+        });
+        let password_mock = server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/users/root")
+                .body(r#"{"sshkey":null,"password":"","passwordEncrypted":false}"#);
+            then.status(200).body("0");
+        });
+        let url = server.url("/api");
+
+        let store = users_store(url)?;
+        let mut previous = UserSettings {
+            first_user: Some(FirstUserSettings::default()),
+            root: Some(RootUserSettings {
+                password: Some("".to_owned()),
+                ssh_public_key: None,
+            }),
+        };
+        let applied = UserSettings {
+            first_user: None,
+            root: Some(RootUserSettings {
+                password: Some("1234".to_owned()),
+                ssh_public_key: Some("keykeykey".to_owned()),
+            }),
+        };
+        store.restore(&previous, &applied).await?;
+
+        user_mock.assert();
+        sshkey_mock.assert();
+        password_mock.assert();
+
+        // the previous password is unknown
+        previous.root = Some(RootUserSettings::default());
+        let result = store.restore(&previous, &applied).await;
+        assert!(matches!(
+            result,
+            Err(ServiceError::CannotRestoreRootPassword)
+        ));
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@ use std::{
     process::{ExitCode, Termination},
 };
 
-use agama_lib::{auth::AuthToken, connection_to};
+use agama_lib::{auth::AuthToken, base_http_client::BaseHTTPClient, connection_to};
 use agama_server::{
     cert::Certificate,
     l10n::helpers,
@@ -13,7 +13,7 @@ use agama_server::{
 };
 use anyhow::Context;
 use axum::{
-    extract::Request as AxumRequest,
+    extract::{ConnectInfo, Request as AxumRequest},
     http::{Request, Response},
     Router,
 };
@@ -222,7 +222,8 @@ async fn handle_https_stream(
         tracing::error!("Error during TSL handshake from {}: {}", addr, err);
     } else {
         let stream = TokioIo::new(tls_stream);
-        let hyper_service = hyper::service::service_fn(move |request: Request<Incoming>| {
+        let hyper_service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+            request.extensions_mut().insert(ConnectInfo(addr));
             service.clone().call(request)
         });
 
@@ -244,7 +245,8 @@ async fn handle_http_stream(
     redirector_service: axum::Router,
 ) {
     let stream = TokioIo::new(tcp_stream);
-    let hyper_service = hyper::service::service_fn(move |request: Request<Incoming>| {
+    let hyper_service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+        request.extensions_mut().insert(ConnectInfo(addr));
         // check if it is local connection or external
         // the to_canonical() converts IPv4-mapped IPv6 addresses
         // to plain IPv4, then is_loopback() works correctly for the IPv4 connections
@@ -280,6 +282,14 @@ async fn start_server(address: String, service: Router, ssl_acceptor: SslAccepto
             panic!("{}", msg)
         });
 
+    serve(listener, service, ssl_acceptor).await
+}
+
+/// Serves the API through the given listener.
+///
+/// Both HTTP and HTTPS connections are accepted, but plain HTTP is redirected to HTTPS unless
+/// the client is local.
+async fn serve(listener: tokio::net::TcpListener, service: Router, ssl_acceptor: SslAcceptor) {
     pin_mut!(listener);
 
     let redirector = https_redirect();
@@ -322,7 +332,14 @@ async fn serve_command(args: ServeArgs) -> anyhow::Result<()> {
 
     let dbus = connection_to(&args.dbus_address).await?;
     let web_ui_dir = args.web_ui_dir.clone().unwrap_or(find_web_ui_dir());
-    let service = web::service(config, tx, dbus, web_ui_dir).await?;
+    // the server uses its own API (e.g., to apply a whole profile) through an internal listener,
+    // so it does not depend on the addresses it is configured to listen on
+    let internal_listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .context("could not create the internal listener")?;
+    let local_api = BaseHTTPClient::default()
+        .with_base_url(&format!("http://{}/api", internal_listener.local_addr()?));
+    let service = web::service(config, tx, dbus, local_api, web_ui_dir).await?;
     // TODO: Move elsewhere? Use a singleton? (It would be nice to use the same
     // generated self-signed certificate on both ports.)
     let ssl_acceptor = if let Ok(ssl_acceptor) = ssl_acceptor(&args.to_certificate()?) {
@@ -337,7 +354,7 @@ async fn serve_command(args: ServeArgs) -> anyhow::Result<()> {
         addresses.push(a)
     }
 
-    let servers: Vec<_> = addresses
+    let mut servers: Vec<_> = addresses
         .iter()
         .map(|a| {
             tokio::spawn(start_server(
@@ -347,6 +364,11 @@ async fn serve_command(args: ServeArgs) -> anyhow::Result<()> {
             ))
         })
         .collect();
+    servers.push(tokio::spawn(serve(
+        internal_listener,
+        service.clone(),
+        ssl_acceptor.clone(),
+    )));
 
     // notify systemd that web server start serving
     if let Ok(true) = sd_notify::booted() {
@@ -6,6 +6,7 @@ pub mod logs;
 pub mod manager;
 pub mod network;
 pub mod questions;
+pub mod settings;
 pub mod software;
 pub mod storage;
 pub mod users;
@@ -7,8 +7,9 @@
 
 use agama_lib::{
     error::ServiceError,
-    manager::{InstallationPhase, ManagerClient},
-    proxies::Manager1Proxy,
+    issue::{Issue, IssueSummary, IssuesByService},
+    manager::{InstallationBlockers, InstallationPhase, InstallerStatus, ManagerClient},
+    proxies::{IssuesProxy, Manager1Proxy},
 };
 use axum::{
     extract::{Request, State},
@@ -18,7 +19,6 @@ use axum::{
     Json, Router,
 };
 use rand::distributions::{Alphanumeric, DistString};
-use serde::Serialize;
 use std::{pin::Pin, process::Command};
 use tokio_stream::{Stream, StreamExt};
 use tower_http::services::ServeFile;
@@ -26,29 +26,40 @@ use tower_http::services::ServeFile;
 use crate::{
     error::Error,
     web::{
-        common::{progress_router, service_status_router},
+        common::{build_issues_proxy, progress_router, service_status_router},
         Event,
     },
 };
 
+/// Objects implementing the issues D-Bus interface: (name, D-Bus service, D-Bus path).
+const ISSUES_OBJECTS: [(&str, &str, &str); 4] = [
+    (
+        "product",
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1/Product",
+    ),
+    (
+        "software",
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1",
+    ),
+    (
+        "storage",
+        "org.opensuse.Agama.Storage1",
+        "/org/opensuse/Agama/Storage1",
+    ),
+    (
+        "users",
+        "org.opensuse.Agama.Manager1",
+        "/org/opensuse/Agama/Users1",
+    ),
+];
+
 #[derive(Clone)]
 pub struct ManagerState<'a> {
     dbus: zbus::Connection,
     manager: ManagerClient<'a>,
-}
-
-/// Holds information about the manager's status.
-#[derive(Clone, Serialize, utoipa::ToSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct InstallerStatus {
-    /// Current installation phase.
-    phase: InstallationPhase,
-    /// Whether the service is busy.
-    is_busy: bool,
-    /// Whether Agama is running on Iguana.
-    use_iguana: bool,
-    /// Whether it is possible to start the installation.
-    can_install: bool,
+    issues: Vec<(&'static str, IssuesProxy<'a>)>,
 }
 
 /// Returns a stream that emits manager related events coming from D-Bus.
@@ -88,12 +99,21 @@ pub async fn manager_service(dbus: zbus::Connection) -> Result<Router, ServiceEr
     let status_router = service_status_router(&dbus, DBUS_SERVICE, DBUS_PATH).await?;
     let progress_router = progress_router(&dbus, DBUS_SERVICE, DBUS_PATH).await?;
     let manager = ManagerClient::new(dbus.clone()).await?;
-    let state = ManagerState { manager, dbus };
+    let mut issues = vec![];
+    for (name, destination, path) in ISSUES_OBJECTS {
+        issues.push((name, build_issues_proxy(&dbus, destination, path).await?));
+    }
+    let state = ManagerState {
+        manager,
+        dbus,
+        issues,
+    };
     Ok(Router::new()
         .route("/probe", post(probe_action))
         .route("/install", post(install_action))
         .route("/finish", post(finish_action))
         .route("/installer", get(installer_status))
+        .route("/issues", get(installer_issues))
         .route("/logs", get(download_logs))
         .merge(status_router)
         .merge(progress_router)
@@ -180,15 +200,81 @@ async fn installer_status(
         InstallationPhase::Install => false,
         _ => state.manager.can_install().await?,
     };
+    let blockers = if can_install || phase == InstallationPhase::Install {
+        InstallationBlockers::default()
+    } else {
+        let busy_services = state.manager.busy_services().await?;
+        InstallationBlockers::new(busy_services, read_available_issues(&state.issues).await)
+    };
     let status = InstallerStatus {
         phase,
         can_install,
         is_busy: state.manager.is_busy().await,
         use_iguana: state.manager.use_iguana().await?,
+        blockers,
     };
     Ok(Json(status))
 }
 
+/// Returns the issues of all the services.
+#[utoipa::path(
+    get,
+    path = "/issues",
+    context_path = "/api/manager",
+    responses(
+      (status = 200, description = "Issues grouped by service (product, software, storage and users).")
+    )
+)]
+async fn installer_issues(
+    State(state): State<ManagerState<'_>>,
+) -> Result<Json<IssuesByService>, Error> {
+    Ok(Json(read_issues(&state.issues).await?))
+}
+
+/// Reads the issues of all the services.
+///
+/// * `proxies`: issues proxies by service name.
+async fn read_issues(
+    proxies: &[(&'static str, IssuesProxy<'_>)],
+) -> Result<IssuesByService, Error> {
+    let mut result = IssuesByService::new();
+    for (name, proxy) in proxies {
+        result.insert(name.to_string(), read_service_issues(proxy).await?);
+    }
+    Ok(result)
+}
+
+/// Reads the issues of the services that are available.
+///
+/// The services whose issues cannot be read are logged and skipped.
+///
+/// * `proxies`: issues proxies by service name.
+async fn read_available_issues(proxies: &[(&'static str, IssuesProxy<'_>)]) -> IssuesByService {
+    let mut result = IssuesByService::new();
+    for (name, proxy) in proxies {
+        match read_service_issues(proxy).await {
+            Ok(issues) => {
+                result.insert(name.to_string(), issues);
+            }
+            Err(error) => {
+                tracing::warn!("Could not read the issues of the {name} service: {error}");
+            }
+        }
+    }
+    result
+}
+
+/// Reads the issues of a service.
+///
+/// * `proxy`: issues proxy of the service.
+async fn read_service_issues(proxy: &IssuesProxy<'_>) -> Result<Vec<IssueSummary>, Error> {
+    let issues = proxy.all().await?;
+    Ok(issues
+        .into_iter()
+        .map(|i| Issue::from_tuple(i).into())
+        .collect())
+}
+
 /// Returns agama logs
 #[utoipa::path(get, path = "/api/manager/logs", responses(
   (status = 200, description = "Download logs blob.")
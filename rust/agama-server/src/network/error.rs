@@ -10,10 +10,14 @@ pub enum NetworkStateError {
     CannotUpdateConnection(String),
     #[error("Unknown device '{0}'")]
     UnknownDevice(String),
+    #[error("Unknown port '{0}': there is no connection or network interface with that name")]
+    UnknownPort(String),
     #[error("Invalid connection UUID: '{0}'")]
     InvalidUuid(String),
     #[error("Invalid IP address: '{0}'")]
     InvalidIpAddr(String),
+    #[error("Unexpected IP family for '{0}'")]
+    UnexpectedIpFamily(String),
     #[error("Invalid IP method: '{0}'")]
     InvalidIpMethod(u8),
     #[error("Invalid wireless mode: '{0}'")]
@@ -36,6 +40,16 @@ pub enum NetworkStateError {
     InvalidWEPAuthAlg(String),
     #[error("Invalid WEP key type: '{0}'")]
     InvalidWEPKeyType(u32),
+    #[error("Invalid VLAN protocol: '{0}'")]
+    InvalidVlanProtocol(String),
+    #[error("Invalid InfiniBand transport mode: '{0}'")]
+    InvalidInfinibandTransportMode(String),
+    #[error("Invalid TUN mode: '{0}'")]
+    InvalidTunMode(String),
+    #[error("Invalid EAP method: '{0}'")]
+    InvalidEAPMethod(String),
+    #[error("Invalid phase2 authentication method: '{0}'")]
+    InvalidPhase2AuthMethod(String),
 }
 
 impl From<NetworkStateError> for zbus::fdo::Error {
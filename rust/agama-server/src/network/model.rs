@@ -3,7 +3,11 @@
 //! * This module contains the types that represent the network concepts. They are supposed to be
 //! agnostic from the real network service (e.g., NetworkManager).
 use crate::network::error::NetworkStateError;
-use agama_lib::network::settings::{BondSettings, NetworkConnection, WirelessSettings};
+use agama_lib::network::settings::{
+    BondSettings, BridgePortSettings, BridgeSettings, IEEE8021XSettings, InfinibandSettings,
+    MatchSettings, NetworkConnection, RouteSettings, RoutingRuleSettings, TunSettings,
+    VlanSettings, WirelessSettings,
+};
 use agama_lib::network::types::{BondMode, DeviceState, DeviceType, Status, SSID};
 use cidr::IpInet;
 use serde::{Deserialize, Serialize};
@@ -190,7 +194,8 @@ impl NetworkState {
 
     /// Sets a controller's ports.
     ///
-    /// If the connection is not a controller, returns an error.
+    /// If the connection is not a controller, returns an error. When there is no connection for
+    /// a port but there is a device with that name, it creates a new connection for the device.
     ///
     /// * `controller`: controller to set ports on.
     /// * `ports`: list of port names (using the connection ID or the interface name).
@@ -199,29 +204,45 @@ impl NetworkState {
         controller: &Connection,
         ports: Vec<String>,
     ) -> Result<(), NetworkStateError> {
-        if let ConnectionConfig::Bond(_) = &controller.config {
-            let mut controlled = vec![];
-            for port in ports {
-                let connection = self
-                    .get_connection_by_interface(&port)
-                    .or_else(|| self.get_connection(&port))
-                    .ok_or(NetworkStateError::UnknownConnection(port))?;
+        if !matches!(
+            controller.config,
+            ConnectionConfig::Bond(_) | ConnectionConfig::Bridge(_)
+        ) {
+            return Err(NetworkStateError::NotControllerConnection(
+                controller.id.to_owned(),
+            ));
+        }
+
+        let mut controlled = vec![];
+        let mut new_ports = vec![];
+        for port in ports {
+            if let Some(connection) = self
+                .get_connection_by_interface(&port)
+                .or_else(|| self.get_connection(&port))
+            {
+                controlled.push(connection.uuid);
+            } else if let Some(device) = self.get_device(&port) {
+                let mut connection = Connection::new(port.clone(), device.type_);
+                connection.interface = Some(port);
                 controlled.push(connection.uuid);
+                new_ports.push(connection);
+            } else {
+                return Err(NetworkStateError::UnknownPort(port));
             }
+        }
+
+        for connection in new_ports {
+            self.add_connection(connection)?;
+        }
 
-            for conn in self.connections.iter_mut() {
-                if controlled.contains(&conn.uuid) {
-                    conn.controller = Some(controller.uuid);
-                } else if conn.controller == Some(controller.uuid) {
-                    conn.controller = None;
-                }
+        for conn in self.connections.iter_mut() {
+            if controlled.contains(&conn.uuid) {
+                conn.controller = Some(controller.uuid);
+            } else if conn.controller == Some(controller.uuid) {
+                conn.controller = None;
             }
-            Ok(())
-        } else {
-            Err(NetworkStateError::NotControllerConnection(
-                controller.id.to_owned(),
-            ))
         }
+        Ok(())
     }
 }
 
@@ -407,7 +428,49 @@ mod tests {
         let error = state
             .set_ports(&bond0, vec!["eth0".to_string()])
             .unwrap_err();
-        assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
+        assert!(matches!(error, NetworkStateError::UnknownPort(_)));
+    }
+
+    #[test]
+    fn test_set_bridge_ports() {
+        let mut state = NetworkState::default();
+        let eth0 = Connection {
+            id: "Wired connection".to_string(),
+            interface: Some("eth0".to_string()),
+            ..Default::default()
+        };
+        let eth1 = Device {
+            name: "eth1".to_string(),
+            type_: DeviceType::Ethernet,
+            ..Default::default()
+        };
+        let br0 = Connection {
+            id: "br0".to_string(),
+            interface: Some("br0".to_string()),
+            config: ConnectionConfig::Bridge(Default::default()),
+            ..Default::default()
+        };
+
+        state.add_connection(eth0.clone()).unwrap();
+        state.add_device(eth1).unwrap();
+        state.add_connection(br0.clone()).unwrap();
+
+        state
+            .set_ports(&br0, vec!["eth0".to_string(), "eth1".to_string()])
+            .unwrap();
+
+        let eth0_found = state.get_connection("Wired connection").unwrap();
+        assert_eq!(eth0_found.controller, Some(br0.uuid));
+        let eth1_found = state.get_connection("eth1").unwrap();
+        assert_eq!(eth1_found.interface, Some("eth1".to_string()));
+        assert_eq!(eth1_found.controller, Some(br0.uuid));
+
+        let error = state
+            .set_ports(&br0, vec!["eth0".to_string(), "eth2".to_string()])
+            .unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnknownPort(_)));
+        let eth0_found = state.get_connection("Wired connection").unwrap();
+        assert_eq!(eth0_found.controller, Some(br0.uuid));
     }
 
     #[test]
@@ -427,6 +490,245 @@ mod tests {
             NetworkStateError::NotControllerConnection(_),
         ));
     }
+
+    #[test]
+    fn test_vlan_settings_round_trip() {
+        let settings = NetworkConnection {
+            id: "eth0.10".to_string(),
+            vlan: Some(VlanSettings {
+                parent: "eth0".to_string(),
+                id: 10,
+                protocol: Some("802.1ad".to_string()),
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings.clone()).unwrap();
+        assert_eq!(
+            conn.config,
+            ConnectionConfig::Vlan(VlanConfig {
+                parent: "eth0".to_string(),
+                id: 10,
+                protocol: VlanProtocol::IEEE802_1ad,
+            })
+        );
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.vlan, settings.vlan);
+        assert_eq!(settings_back.device_type(), DeviceType::Vlan);
+    }
+
+    #[test]
+    fn test_bridge_settings_round_trip() {
+        let bridge = BridgeSettings {
+            stp: Some(true),
+            priority: Some(32768),
+            forward_delay: Some(15),
+            hello_time: Some(2),
+            max_age: Some(20),
+            ageing_time: Some(300),
+            ..Default::default()
+        };
+        let settings = NetworkConnection {
+            id: "br0".to_string(),
+            bridge: Some(bridge.clone()),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let ConnectionConfig::Bridge(config) = &conn.config else {
+            panic!("Unexpected connection config: {:?}", conn.config);
+        };
+        assert!(config.stp);
+        assert_eq!(config.forward_delay, Some(15));
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.bridge, Some(bridge));
+    }
+
+    #[test]
+    fn test_bridge_port_settings_round_trip() {
+        let bridge_port = BridgePortSettings {
+            priority: Some(32),
+            path_cost: Some(100),
+        };
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            bridge_port: Some(bridge_port.clone()),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        assert_eq!(
+            conn.port_config,
+            PortConfig::Bridge(BridgePortConfig {
+                priority: Some(32),
+                path_cost: Some(100)
+            })
+        );
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.bridge_port, Some(bridge_port));
+    }
+
+    #[test]
+    fn test_infiniband_settings_round_trip() {
+        let infiniband = InfinibandSettings {
+            p_key: Some(0x8001),
+            parent: Some("ib0".to_string()),
+            transport_mode: Some("connected".to_string()),
+        };
+        let settings = NetworkConnection {
+            id: "ib0.8001".to_string(),
+            infiniband: Some(infiniband.clone()),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let ConnectionConfig::Infiniband(config) = &conn.config else {
+            panic!("Unexpected connection config: {:?}", conn.config);
+        };
+        assert_eq!(config.transport_mode, InfinibandTransportMode::Connected);
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.infiniband, Some(infiniband));
+    }
+
+    #[test]
+    fn test_tun_settings_round_trip() {
+        let tun = TunSettings {
+            mode: Some("tap".to_string()),
+            group: Some("users".to_string()),
+            owner: Some("root".to_string()),
+        };
+        let settings = NetworkConnection {
+            id: "tap0".to_string(),
+            tun: Some(tun.clone()),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let ConnectionConfig::Tun(config) = &conn.config else {
+            panic!("Unexpected connection config: {:?}", conn.config);
+        };
+        assert_eq!(config.mode, TunMode::Tap);
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.tun, Some(tun));
+    }
+
+    #[test]
+    fn test_ieee_8021x_settings_round_trip() {
+        let ieee_8021x = IEEE8021XSettings {
+            eap: vec!["peap".to_string(), "ttls".to_string()],
+            phase2_auth: Some("mschapv2".to_string()),
+            identity: Some("agama".to_string()),
+            password: Some("nots3cr3t".to_string()),
+            anonymous_identity: Some("anonymous".to_string()),
+            ca_cert: Some("/etc/pki/ca.pem".to_string()),
+            ..Default::default()
+        };
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            ieee_8021x: Some(ieee_8021x.clone()),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let config = conn.ieee_8021x_config.as_ref().unwrap();
+        assert_eq!(config.eap, vec![EAPMethod::Peap, EAPMethod::Ttls]);
+        assert_eq!(config.phase2_auth, Some(Phase2AuthMethod::Mschapv2));
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.ieee_8021x, Some(ieee_8021x));
+
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            ieee_8021x: Some(IEEE8021XSettings {
+                eap: vec!["eap-tls".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidEAPMethod(_)));
+    }
+
+    #[test]
+    fn test_routes_settings_round_trip() {
+        let route4 = RouteSettings {
+            destination: "10.0.0.0/8".parse().unwrap(),
+            next_hop: Some("192.168.0.1".parse().unwrap()),
+            metric: Some(100),
+            table: Some(200),
+        };
+        let route6 = RouteSettings {
+            destination: "2001:db8::/64".parse().unwrap(),
+            next_hop: None,
+            metric: None,
+            table: None,
+        };
+        let rule4 = RoutingRuleSettings {
+            priority: 1000,
+            from: Some("192.168.1.0/24".parse().unwrap()),
+            to: None,
+            table: 200,
+        };
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            routes4: Some(vec![route4.clone()]),
+            routes6: Some(vec![route6.clone()]),
+            routing_rules4: Some(vec![rule4.clone()]),
+            routing_rules6: Some(vec![]),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let routes4 = conn.ip_config.routes4.as_ref().unwrap();
+        assert_eq!(routes4[0].table, Some(200));
+        assert_eq!(conn.ip_config.routing_rules6, Some(vec![]));
+
+        let settings_back = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings_back.routes4, Some(vec![route4]));
+        assert_eq!(settings_back.routes6, Some(vec![route6.clone()]));
+        assert_eq!(settings_back.routing_rules4, Some(vec![rule4.clone()]));
+        assert_eq!(settings_back.routing_rules6, Some(vec![]));
+
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            routes4: Some(vec![route6]),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnexpectedIpFamily(_)));
+
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            routing_rules6: Some(vec![rule4]),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::UnexpectedIpFamily(_)));
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let settings = NetworkConnection {
+            id: "tun0".to_string(),
+            tun: Some(TunSettings {
+                mode: Some("tcp".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidTunMode(_)));
+
+        let settings = NetworkConnection {
+            id: "eth0.10".to_string(),
+            vlan: Some(VlanSettings {
+                parent: "eth0".to_string(),
+                id: 10,
+                protocol: Some("802.1X".to_string()),
+            }),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidVlanProtocol(_)));
+    }
 }
 
 /// Network state
@@ -490,6 +792,7 @@ pub struct Connection {
     pub port_config: PortConfig,
     pub match_config: MatchConfig,
     pub config: ConnectionConfig,
+    pub ieee_8021x_config: Option<IEEE8021XConfig>,
 }
 
 impl Connection {
@@ -502,6 +805,8 @@ impl Connection {
             DeviceType::Bond => ConnectionConfig::Bond(Default::default()),
             DeviceType::Vlan => ConnectionConfig::Vlan(Default::default()),
             DeviceType::Bridge => ConnectionConfig::Bridge(Default::default()),
+            DeviceType::Infiniband => ConnectionConfig::Infiniband(Default::default()),
+            DeviceType::Tun => ConnectionConfig::Tun(Default::default()),
         };
         Self {
             id,
@@ -560,6 +865,7 @@ impl Default for Connection {
             port_config: Default::default(),
             match_config: Default::default(),
             config: Default::default(),
+            ieee_8021x_config: Default::default(),
         }
     }
 }
@@ -599,14 +905,70 @@ impl TryFrom<NetworkConnection> for Connection {
             connection.config = config.into();
         }
 
+        if let Some(vlan_config) = conn.vlan {
+            let config = VlanConfig::try_from(vlan_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(bridge_config) = conn.bridge {
+            let config = BridgeConfig::from(bridge_config);
+            connection.config = config.into();
+        }
+
+        if let Some(infiniband_config) = conn.infiniband {
+            let config = InfinibandConfig::try_from(infiniband_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(tun_config) = conn.tun {
+            let config = TunConfig::try_from(tun_config)?;
+            connection.config = config.into();
+        }
+
         connection.ip_config.addresses = conn.addresses;
         connection.ip_config.nameservers = conn.nameservers;
         connection.ip_config.dns_searchlist = conn.dns_searchlist;
         connection.ip_config.gateway4 = conn.gateway4;
         connection.ip_config.gateway6 = conn.gateway6;
+
+        check_ip_family(conn.routes4.iter().flatten().map(|r| r.destination), true)?;
+        check_ip_family(conn.routes6.iter().flatten().map(|r| r.destination), false)?;
+        connection.ip_config.routes4 = from_settings(conn.routes4);
+        connection.ip_config.routes6 = from_settings(conn.routes6);
+
+        let rule_addresses = |r: &RoutingRuleSettings| [r.from, r.to].into_iter().flatten();
+        check_ip_family(
+            conn.routing_rules4
+                .iter()
+                .flatten()
+                .flat_map(rule_addresses),
+            true,
+        )?;
+        check_ip_family(
+            conn.routing_rules6
+                .iter()
+                .flatten()
+                .flat_map(rule_addresses),
+            false,
+        )?;
+        connection.ip_config.routing_rules4 = from_settings(conn.routing_rules4);
+        connection.ip_config.routing_rules6 = from_settings(conn.routing_rules6);
+
         connection.interface = conn.interface;
         connection.mtu = conn.mtu;
 
+        if let Some(match_settings) = conn.match_settings {
+            connection.match_config = MatchConfig::from(match_settings);
+        }
+
+        if let Some(bridge_port) = conn.bridge_port {
+            connection.port_config = PortConfig::Bridge(bridge_port.into());
+        }
+
+        if let Some(ieee_8021x) = conn.ieee_8021x {
+            connection.ieee_8021x_config = Some(IEEE8021XConfig::try_from(ieee_8021x)?);
+        }
+
         Ok(connection)
     }
 }
@@ -626,9 +988,20 @@ impl TryFrom<Connection> for NetworkConnection {
         let addresses = conn.ip_config.addresses;
         let gateway4 = conn.ip_config.gateway4;
         let gateway6 = conn.ip_config.gateway6;
+        let routes4 = into_settings(conn.ip_config.routes4);
+        let routes6 = into_settings(conn.ip_config.routes6);
+        let routing_rules4 = into_settings(conn.ip_config.routing_rules4);
+        let routing_rules6 = into_settings(conn.ip_config.routing_rules6);
         let interface = conn.interface;
         let status = Some(conn.status);
         let mtu = conn.mtu;
+        let match_settings = MatchSettings::from(conn.match_config);
+        let match_settings = (!match_settings.is_empty()).then_some(match_settings);
+        let bridge_port = match conn.port_config {
+            PortConfig::Bridge(config) => Some(BridgePortSettings::from(config)),
+            PortConfig::None => None,
+        };
+        let ieee_8021x = conn.ieee_8021x_config.map(IEEE8021XSettings::from);
 
         let mut connection = NetworkConnection {
             id,
@@ -637,6 +1010,10 @@ impl TryFrom<Connection> for NetworkConnection {
             method6,
             gateway4,
             gateway6,
+            routes4,
+            routes6,
+            routing_rules4,
+            routing_rules6,
             nameservers,
             dns_searchlist,
             ignore_auto_dns,
@@ -644,6 +1021,9 @@ impl TryFrom<Connection> for NetworkConnection {
             interface,
             addresses,
             mtu,
+            match_settings,
+            bridge_port,
+            ieee_8021x,
             ..Default::default()
         };
 
@@ -654,6 +1034,18 @@ impl TryFrom<Connection> for NetworkConnection {
             ConnectionConfig::Bond(config) => {
                 connection.bond = Some(BondSettings::try_from(config)?);
             }
+            ConnectionConfig::Vlan(config) => {
+                connection.vlan = Some(VlanSettings::from(config));
+            }
+            ConnectionConfig::Bridge(config) => {
+                connection.bridge = Some(BridgeSettings::from(config));
+            }
+            ConnectionConfig::Infiniband(config) => {
+                connection.infiniband = Some(InfinibandSettings::from(config));
+            }
+            ConnectionConfig::Tun(config) => {
+                connection.tun = Some(TunSettings::from(config));
+            }
             _ => {}
         }
 
@@ -694,6 +1086,30 @@ impl From<WirelessConfig> for ConnectionConfig {
     }
 }
 
+impl From<VlanConfig> for ConnectionConfig {
+    fn from(value: VlanConfig) -> Self {
+        Self::Vlan(value)
+    }
+}
+
+impl From<BridgeConfig> for ConnectionConfig {
+    fn from(value: BridgeConfig) -> Self {
+        Self::Bridge(value)
+    }
+}
+
+impl From<InfinibandConfig> for ConnectionConfig {
+    fn from(value: InfinibandConfig) -> Self {
+        Self::Infiniband(value)
+    }
+}
+
+impl From<TunConfig> for ConnectionConfig {
+    fn from(value: TunConfig) -> Self {
+        Self::Tun(value)
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Invalid MAC address: {0}")]
 pub struct InvalidMacAddress(String);
@@ -775,6 +1191,8 @@ pub struct IpConfig {
     pub gateway6: Option<IpAddr>,
     pub routes4: Option<Vec<IpRoute>>,
     pub routes6: Option<Vec<IpRoute>>,
+    pub routing_rules4: Option<Vec<IpRoutingRule>>,
+    pub routing_rules6: Option<Vec<IpRoutingRule>>,
 }
 
 #[skip_serializing_none]
@@ -790,6 +1208,28 @@ pub struct MatchConfig {
     pub kernel: Vec<String>,
 }
 
+impl From<MatchSettings> for MatchConfig {
+    fn from(settings: MatchSettings) -> Self {
+        MatchConfig {
+            driver: settings.driver,
+            interface: settings.interface,
+            path: settings.path,
+            kernel: settings.kernel,
+        }
+    }
+}
+
+impl From<MatchConfig> for MatchSettings {
+    fn from(config: MatchConfig) -> Self {
+        MatchSettings {
+            driver: config.driver,
+            path: config.path,
+            kernel: config.kernel,
+            interface: config.interface,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Unknown IP configuration method name: {0}")]
 pub struct UnknownIpMethod(String);
@@ -886,6 +1326,8 @@ pub struct IpRoute {
     pub next_hop: Option<IpAddr>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metric: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<u32>,
 }
 
 impl From<&IpRoute> for HashMap<&str, Value<'_>> {
@@ -903,10 +1345,94 @@ impl From<&IpRoute> for HashMap<&str, Value<'_>> {
         if let Some(metric) = route.metric {
             map.insert("metric", Value::new(metric));
         }
+        if let Some(table) = route.table {
+            map.insert("table", Value::new(table));
+        }
         map
     }
 }
 
+impl From<RouteSettings> for IpRoute {
+    fn from(settings: RouteSettings) -> Self {
+        IpRoute {
+            destination: settings.destination,
+            next_hop: settings.next_hop,
+            metric: settings.metric,
+            table: settings.table,
+        }
+    }
+}
+
+impl From<IpRoute> for RouteSettings {
+    fn from(route: IpRoute) -> Self {
+        RouteSettings {
+            destination: route.destination,
+            next_hop: route.next_hop,
+            metric: route.metric,
+            table: route.table,
+        }
+    }
+}
+
+/// Routing policy rule which sends the matching traffic to the given table.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpRoutingRule {
+    pub priority: u32,
+    pub from: Option<IpInet>,
+    pub to: Option<IpInet>,
+    pub table: u32,
+}
+
+impl From<RoutingRuleSettings> for IpRoutingRule {
+    fn from(settings: RoutingRuleSettings) -> Self {
+        IpRoutingRule {
+            priority: settings.priority,
+            from: settings.from,
+            to: settings.to,
+            table: settings.table,
+        }
+    }
+}
+
+impl From<IpRoutingRule> for RoutingRuleSettings {
+    fn from(rule: IpRoutingRule) -> Self {
+        RoutingRuleSettings {
+            priority: rule.priority,
+            from: rule.from,
+            to: rule.to,
+            table: rule.table,
+        }
+    }
+}
+
+/// Converts an optional list of routes or routing rules into their settings.
+fn into_settings<T, S: From<T>>(values: Option<Vec<T>>) -> Option<Vec<S>> {
+    values.map(|v| v.into_iter().map(S::from).collect())
+}
+
+/// Converts an optional list of routes or routing rules settings.
+///
+/// `None` means that the existing routes or rules are kept, while an empty list removes them.
+fn from_settings<S, T: From<S>>(values: Option<Vec<S>>) -> Option<Vec<T>> {
+    values.map(|v| v.into_iter().map(T::from).collect())
+}
+
+/// Checks that all the addresses belong to the same IP family.
+///
+/// * `addresses`: addresses to check.
+/// * `ipv4`: whether the addresses are expected to be IPv4 ones.
+fn check_ip_family(
+    addresses: impl IntoIterator<Item = IpInet>,
+    ipv4: bool,
+) -> Result<(), NetworkStateError> {
+    match addresses.into_iter().find(|a| a.is_ipv4() != ipv4) {
+        Some(address) => Err(NetworkStateError::UnexpectedIpFamily(address.to_string())),
+        None => Ok(()),
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Serialize)]
 pub enum VlanProtocol {
     #[default]
@@ -947,6 +1473,34 @@ pub struct VlanConfig {
     pub protocol: VlanProtocol,
 }
 
+impl TryFrom<VlanSettings> for VlanConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: VlanSettings) -> Result<Self, Self::Error> {
+        let mut protocol = VlanProtocol::default();
+        if let Some(settings_protocol) = settings.protocol {
+            protocol = VlanProtocol::from_str(&settings_protocol)
+                .map_err(|_| NetworkStateError::InvalidVlanProtocol(settings_protocol))?;
+        }
+
+        Ok(VlanConfig {
+            parent: settings.parent,
+            id: settings.id,
+            protocol,
+        })
+    }
+}
+
+impl From<VlanConfig> for VlanSettings {
+    fn from(vlan: VlanConfig) -> Self {
+        VlanSettings {
+            parent: vlan.parent,
+            id: vlan.id,
+            protocol: Some(vlan.protocol.to_string()),
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Default, PartialEq, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -1278,6 +1832,33 @@ pub struct BridgeConfig {
     pub ageing_time: Option<u32>,
 }
 
+impl From<BridgeSettings> for BridgeConfig {
+    fn from(settings: BridgeSettings) -> Self {
+        BridgeConfig {
+            stp: settings.stp.unwrap_or_default(),
+            priority: settings.priority,
+            forward_delay: settings.forward_delay,
+            hello_time: settings.hello_time,
+            max_age: settings.max_age,
+            ageing_time: settings.ageing_time,
+        }
+    }
+}
+
+impl From<BridgeConfig> for BridgeSettings {
+    fn from(bridge: BridgeConfig) -> Self {
+        BridgeSettings {
+            stp: Some(bridge.stp),
+            priority: bridge.priority,
+            forward_delay: bridge.forward_delay,
+            hello_time: bridge.hello_time,
+            max_age: bridge.max_age,
+            ageing_time: bridge.ageing_time,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Serialize)]
 pub struct BridgePortConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1286,6 +1867,24 @@ pub struct BridgePortConfig {
     pub path_cost: Option<u32>,
 }
 
+impl From<BridgePortSettings> for BridgePortConfig {
+    fn from(settings: BridgePortSettings) -> Self {
+        BridgePortConfig {
+            priority: settings.priority,
+            path_cost: settings.path_cost,
+        }
+    }
+}
+
+impl From<BridgePortConfig> for BridgePortSettings {
+    fn from(config: BridgePortConfig) -> Self {
+        BridgePortSettings {
+            priority: config.priority,
+            path_cost: config.path_cost,
+        }
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize)]
 pub struct InfinibandConfig {
     pub p_key: Option<i32>,
@@ -1293,6 +1892,34 @@ pub struct InfinibandConfig {
     pub transport_mode: InfinibandTransportMode,
 }
 
+impl TryFrom<InfinibandSettings> for InfinibandConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: InfinibandSettings) -> Result<Self, Self::Error> {
+        let mut transport_mode = InfinibandTransportMode::default();
+        if let Some(settings_mode) = settings.transport_mode {
+            transport_mode = InfinibandTransportMode::from_str(&settings_mode)
+                .map_err(|_| NetworkStateError::InvalidInfinibandTransportMode(settings_mode))?;
+        }
+
+        Ok(InfinibandConfig {
+            p_key: settings.p_key,
+            parent: settings.parent,
+            transport_mode,
+        })
+    }
+}
+
+impl From<InfinibandConfig> for InfinibandSettings {
+    fn from(infiniband: InfinibandConfig) -> Self {
+        InfinibandSettings {
+            p_key: infiniband.p_key,
+            parent: infiniband.parent,
+            transport_mode: Some(infiniband.transport_mode.to_string()),
+        }
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize)]
 pub enum InfinibandTransportMode {
     #[default]
@@ -1333,6 +1960,28 @@ pub enum TunMode {
     Tap = 2,
 }
 
+impl FromStr for TunMode {
+    type Err = NetworkStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tun" => Ok(Self::Tun),
+            "tap" => Ok(Self::Tap),
+            _ => Err(NetworkStateError::InvalidTunMode(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TunMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            TunMode::Tun => "tun",
+            TunMode::Tap => "tap",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize)]
 pub struct TunConfig {
     pub mode: TunMode,
@@ -1340,6 +1989,184 @@ pub struct TunConfig {
     pub owner: Option<String>,
 }
 
+impl TryFrom<TunSettings> for TunConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: TunSettings) -> Result<Self, Self::Error> {
+        let mut mode = TunMode::default();
+        if let Some(settings_mode) = settings.mode {
+            mode = TunMode::from_str(&settings_mode)?;
+        }
+
+        Ok(TunConfig {
+            mode,
+            group: settings.group,
+            owner: settings.owner,
+        })
+    }
+}
+
+impl From<TunConfig> for TunSettings {
+    fn from(tun: TunConfig) -> Self {
+        TunSettings {
+            mode: Some(tun.mode.to_string()),
+            group: tun.group,
+            owner: tun.owner,
+        }
+    }
+}
+
+/// 802.1X (EAP) authentication settings.
+///
+/// The certificates and the private key can be given as a path or as inline PEM data.
+#[skip_serializing_none]
+#[derive(Default, Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IEEE8021XConfig {
+    pub eap: Vec<EAPMethod>,
+    pub phase2_auth: Option<Phase2AuthMethod>,
+    pub identity: Option<String>,
+    pub password: Option<String>,
+    pub anonymous_identity: Option<String>,
+    pub ca_cert: Option<String>,
+    pub client_cert: Option<String>,
+    pub private_key: Option<String>,
+    pub private_key_password: Option<String>,
+}
+
+impl TryFrom<IEEE8021XSettings> for IEEE8021XConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: IEEE8021XSettings) -> Result<Self, Self::Error> {
+        let eap = settings
+            .eap
+            .iter()
+            .map(|m| EAPMethod::from_str(m))
+            .collect::<Result<Vec<_>, _>>()?;
+        let phase2_auth = settings
+            .phase2_auth
+            .map(|m| Phase2AuthMethod::from_str(&m))
+            .transpose()?;
+
+        Ok(IEEE8021XConfig {
+            eap,
+            phase2_auth,
+            identity: settings.identity,
+            password: settings.password,
+            anonymous_identity: settings.anonymous_identity,
+            ca_cert: settings.ca_cert,
+            client_cert: settings.client_cert,
+            private_key: settings.private_key,
+            private_key_password: settings.private_key_password,
+        })
+    }
+}
+
+impl From<IEEE8021XConfig> for IEEE8021XSettings {
+    fn from(config: IEEE8021XConfig) -> Self {
+        IEEE8021XSettings {
+            eap: config.eap.iter().map(|m| m.to_string()).collect(),
+            phase2_auth: config.phase2_auth.map(|m| m.to_string()),
+            identity: config.identity,
+            password: config.password,
+            anonymous_identity: config.anonymous_identity,
+            ca_cert: config.ca_cert,
+            client_cert: config.client_cert,
+            private_key: config.private_key,
+            private_key_password: config.private_key_password,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum EAPMethod {
+    Leap,
+    Md5,
+    Tls,
+    Peap,
+    Ttls,
+    Pwd,
+    Fast,
+}
+
+impl FromStr for EAPMethod {
+    type Err = NetworkStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "leap" => Ok(Self::Leap),
+            "md5" => Ok(Self::Md5),
+            "tls" => Ok(Self::Tls),
+            "peap" => Ok(Self::Peap),
+            "ttls" => Ok(Self::Ttls),
+            "pwd" => Ok(Self::Pwd),
+            "fast" => Ok(Self::Fast),
+            _ => Err(NetworkStateError::InvalidEAPMethod(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for EAPMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match &self {
+            Self::Leap => "leap",
+            Self::Md5 => "md5",
+            Self::Tls => "tls",
+            Self::Peap => "peap",
+            Self::Ttls => "ttls",
+            Self::Pwd => "pwd",
+            Self::Fast => "fast",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Phase2AuthMethod {
+    Pap,
+    Chap,
+    Mschap,
+    Mschapv2,
+    Gtc,
+    Otp,
+    Md5,
+    Tls,
+}
+
+impl FromStr for Phase2AuthMethod {
+    type Err = NetworkStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pap" => Ok(Self::Pap),
+            "chap" => Ok(Self::Chap),
+            "mschap" => Ok(Self::Mschap),
+            "mschapv2" => Ok(Self::Mschapv2),
+            "gtc" => Ok(Self::Gtc),
+            "otp" => Ok(Self::Otp),
+            "md5" => Ok(Self::Md5),
+            "tls" => Ok(Self::Tls),
+            _ => Err(NetworkStateError::InvalidPhase2AuthMethod(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Phase2AuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match &self {
+            Self::Pap => "pap",
+            Self::Chap => "chap",
+            Self::Mschap => "mschap",
+            Self::Mschapv2 => "mschapv2",
+            Self::Gtc => "gtc",
+            Self::Otp => "otp",
+            Self::Md5 => "md5",
+            Self::Tls => "tls",
+        };
+        write!(f, "{}", value)
+    }
+}
+
 /// Represents a network change.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -202,6 +202,7 @@ impl<'a> DeviceFromProxyBuilder<'a> {
             destination,
             next_hop: None,
             metric: None,
+            table: None,
         };
 
         if let Some(next_hop) = route_data.get("next-hop") {
@@ -212,6 +213,10 @@ impl<'a> DeviceFromProxyBuilder<'a> {
             let metric: u32 = *metric.downcast_ref()?;
             new_route.metric = Some(metric);
         }
+        if let Some(table) = route_data.get("table") {
+            let table: u32 = *table.downcast_ref()?;
+            new_route.table = Some(table);
+        }
 
         Some(new_route)
     }
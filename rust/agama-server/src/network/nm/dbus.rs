@@ -25,6 +25,11 @@ const BRIDGE_KEY: &str = "bridge";
 const BRIDGE_PORT_KEY: &str = "bridge-port";
 const INFINIBAND_KEY: &str = "infiniband";
 const TUN_KEY: &str = "tun";
+const IEEE_8021X_KEY: &str = "802-1x";
+
+/// Address families, as used by the routing rules.
+const AF_INET: i32 = 2;
+const AF_INET6: i32 = 10;
 
 /// Converts a connection struct into a HashMap that can be sent over D-Bus.
 ///
@@ -137,6 +142,10 @@ pub fn connection_to_dbus<'a>(
         PortConfig::None => {}
     }
 
+    if let Some(ieee_8021x_config) = &conn.ieee_8021x_config {
+        result.insert(IEEE_8021X_KEY, ieee_8021x_config_to_dbus(ieee_8021x_config));
+    }
+
     result.insert("connection", connection_dbus);
     result
 }
@@ -151,6 +160,8 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Option<Connection> {
         connection.port_config = PortConfig::Bridge(bridge_port_config);
     }
 
+    connection.ieee_8021x_config = ieee_8021x_config_from_dbus(&conn);
+
     if let Some(wireless_config) = wireless_config_from_dbus(&conn) {
         connection.config = ConnectionConfig::Wireless(wireless_config);
         return Some(connection);
@@ -201,6 +212,7 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Option<Connection> {
 /// Merges a NestedHash and an OwnedNestedHash connections.
 ///
 /// Only the top-level sections that are present in the `original` hash are considered for update.
+/// As an exception, the 802.1X section is taken from the `updated` hash as a whole.
 ///
 /// * `original`: original hash coming from D-Bus.
 /// * `updated`: updated hash to write to D-Bus.
@@ -222,6 +234,17 @@ pub fn merge_dbus_connections<'a>(
         }
         merged.insert(key.as_str(), inner);
     }
+    match updated.get(IEEE_8021X_KEY) {
+        Some(ieee_8021x) => {
+            merged
+                .entry(IEEE_8021X_KEY)
+                .or_insert_with(|| ieee_8021x.clone());
+        }
+        // 802.1X is not configured anymore
+        None => {
+            merged.remove(IEEE_8021X_KEY);
+        }
+    }
     cleanup_dbus_connection(&mut merged);
     merged
 }
@@ -313,12 +336,44 @@ fn ip_config_to_ipv4_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value
         );
     }
 
+    if let Some(rules4) = &ip_config.routing_rules4 {
+        ipv4_dbus.insert(
+            "routing-rules",
+            rules4
+                .iter()
+                .map(|rule| routing_rule_to_dbus(rule, AF_INET))
+                .collect::<Vec<HashMap<&str, Value>>>()
+                .into(),
+        );
+    }
+
     if let Some(gateway) = &ip_config.gateway4 {
         ipv4_dbus.insert("gateway", gateway.to_string().into());
     }
     ipv4_dbus
 }
 
+/// Converts a routing rule to the NetworkManager format.
+///
+/// * `rule`: routing rule.
+/// * `family`: address family of the rule (`AF_INET` or `AF_INET6`).
+fn routing_rule_to_dbus(rule: &IpRoutingRule, family: i32) -> HashMap<&str, Value<'_>> {
+    let mut map: HashMap<&str, Value> = HashMap::from([
+        ("family", Value::new(family)),
+        ("priority", Value::new(rule.priority)),
+        ("table", Value::new(rule.table)),
+    ]);
+    if let Some(from) = rule.from {
+        map.insert("from", Value::new(from.address().to_string()));
+        map.insert("from-len", Value::new(from.network_length()));
+    }
+    if let Some(to) = rule.to {
+        map.insert("to", Value::new(to.address().to_string()));
+        map.insert("to-len", Value::new(to.network_length()));
+    }
+    map
+}
+
 fn ip_config_to_ipv6_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value> {
     let addresses: Vec<HashMap<&str, Value>> = ip_config
         .addresses
@@ -360,6 +415,17 @@ fn ip_config_to_ipv6_dbus(ip_config: &IpConfig) -> HashMap<&str, zvariant::Value
         );
     }
 
+    if let Some(rules6) = &ip_config.routing_rules6 {
+        ipv6_dbus.insert(
+            "routing-rules",
+            rules6
+                .iter()
+                .map(|rule| routing_rule_to_dbus(rule, AF_INET6))
+                .collect::<Vec<HashMap<&str, Value>>>()
+                .into(),
+        );
+    }
+
     if let Some(gateway) = &ip_config.gateway6 {
         ipv6_dbus.insert("gateway", gateway.to_string().into());
     }
@@ -584,6 +650,135 @@ fn tun_config_from_dbus(conn: &OwnedNestedHash) -> Option<TunConfig> {
     Some(tun_config)
 }
 
+/// Converts an IEEE8021XConfig struct into a HashMap that can be sent over D-Bus.
+///
+/// * `config`: 802.1X configuration to convert.
+fn ieee_8021x_config_to_dbus(config: &IEEE8021XConfig) -> HashMap<&str, zvariant::Value<'_>> {
+    let eap: Vec<String> = config.eap.iter().map(|m| m.to_string()).collect();
+    let mut ieee_8021x_config: HashMap<&str, zvariant::Value> =
+        HashMap::from([("eap", Value::new(eap))]);
+
+    if let Some(phase2_auth) = &config.phase2_auth {
+        ieee_8021x_config.insert("phase2-auth", phase2_auth.to_string().into());
+    }
+
+    if let Some(identity) = &config.identity {
+        ieee_8021x_config.insert("identity", identity.into());
+    }
+
+    if let Some(password) = &config.password {
+        ieee_8021x_config.insert("password", password.into());
+    }
+
+    if let Some(anonymous_identity) = &config.anonymous_identity {
+        ieee_8021x_config.insert("anonymous-identity", anonymous_identity.into());
+    }
+
+    if let Some(ca_cert) = &config.ca_cert {
+        ieee_8021x_config.insert("ca-cert", certificate_to_dbus(ca_cert).into());
+    }
+
+    if let Some(client_cert) = &config.client_cert {
+        ieee_8021x_config.insert("client-cert", certificate_to_dbus(client_cert).into());
+    }
+
+    if let Some(private_key) = &config.private_key {
+        ieee_8021x_config.insert("private-key", certificate_to_dbus(private_key).into());
+    }
+
+    if let Some(private_key_password) = &config.private_key_password {
+        ieee_8021x_config.insert("private-key-password", private_key_password.into());
+    }
+
+    ieee_8021x_config
+}
+
+fn ieee_8021x_config_from_dbus(conn: &OwnedNestedHash) -> Option<IEEE8021XConfig> {
+    let ieee_8021x = conn.get(IEEE_8021X_KEY)?;
+
+    let mut ieee_8021x_config = IEEE8021XConfig::default();
+
+    if let Some(eap) = ieee_8021x.get("eap") {
+        let eap: &zvariant::Array = eap.downcast_ref()?;
+        ieee_8021x_config.eap = eap
+            .get()
+            .iter()
+            .filter_map(|m| EAPMethod::from_str(m.downcast_ref::<str>()?).ok())
+            .collect();
+    }
+
+    if let Some(phase2_auth) = ieee_8021x.get("phase2-auth") {
+        ieee_8021x_config.phase2_auth =
+            Phase2AuthMethod::from_str(phase2_auth.downcast_ref::<str>()?).ok();
+    }
+
+    if let Some(identity) = ieee_8021x.get("identity") {
+        ieee_8021x_config.identity = Some(identity.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(password) = ieee_8021x.get("password") {
+        ieee_8021x_config.password = Some(password.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(anonymous_identity) = ieee_8021x.get("anonymous-identity") {
+        ieee_8021x_config.anonymous_identity =
+            Some(anonymous_identity.downcast_ref::<str>()?.to_string());
+    }
+
+    if let Some(ca_cert) = ieee_8021x.get("ca-cert") {
+        ieee_8021x_config.ca_cert = certificate_from_dbus(ca_cert);
+    }
+
+    if let Some(client_cert) = ieee_8021x.get("client-cert") {
+        ieee_8021x_config.client_cert = certificate_from_dbus(client_cert);
+    }
+
+    if let Some(private_key) = ieee_8021x.get("private-key") {
+        ieee_8021x_config.private_key = certificate_from_dbus(private_key);
+    }
+
+    if let Some(private_key_password) = ieee_8021x.get("private-key-password") {
+        ieee_8021x_config.private_key_password =
+            Some(private_key_password.downcast_ref::<str>()?.to_string());
+    }
+
+    Some(ieee_8021x_config)
+}
+
+/// Converts a certificate (or a private key) into the value expected by NetworkManager.
+///
+/// Inline PEM data is sent as it is, while paths use the "file://" scheme.
+///
+/// * `cert`: path or PEM data.
+fn certificate_to_dbus(cert: &str) -> Vec<u8> {
+    if cert.trim_start().starts_with("-----BEGIN") {
+        return cert.as_bytes().to_vec();
+    }
+
+    let mut value = format!("file://{cert}").into_bytes();
+    value.push(0);
+    value
+}
+
+/// Converts a certificate (or a private key) from NetworkManager into a path or PEM data.
+///
+/// It returns `None` if the certificate is not a path or PEM data (e.g., DER data).
+///
+/// * `value`: certificate value.
+fn certificate_from_dbus(value: &OwnedValue) -> Option<String> {
+    let value: &zvariant::Array = value.downcast_ref()?;
+    let value: Vec<u8> = value
+        .get()
+        .iter()
+        .map(|u| u.downcast_ref::<u8>().copied())
+        .collect::<Option<_>>()?;
+    let cert = String::from_utf8(value).ok()?;
+    match cert.strip_prefix("file://") {
+        Some(path) => Some(path.trim_end_matches('\0').to_string()),
+        None => Some(cert),
+    }
+}
+
 /// Converts a MatchConfig struct into a HashMap that can be sent over D-Bus.
 ///
 /// * `match_config`: MatchConfig to convert.
@@ -744,6 +939,10 @@ fn ip_config_from_dbus(conn: &OwnedNestedHash) -> Option<IpConfig> {
             ip_config.routes4 = routes_from_dbus(route_data);
         }
 
+        if let Some(rules) = ipv4.get("routing-rules") {
+            ip_config.routing_rules4 = routing_rules_from_dbus(rules);
+        }
+
         if let Some(gateway) = ipv4.get("gateway") {
             let gateway: &str = gateway.downcast_ref()?;
             ip_config.gateway4 = Some(gateway.parse().unwrap());
@@ -786,6 +985,10 @@ fn ip_config_from_dbus(conn: &OwnedNestedHash) -> Option<IpConfig> {
             ip_config.routes6 = routes_from_dbus(route_data);
         }
 
+        if let Some(rules) = ipv6.get("routing-rules") {
+            ip_config.routing_rules6 = routing_rules_from_dbus(rules);
+        }
+
         if let Some(gateway) = ipv6.get("gateway") {
             let gateway: &str = gateway.downcast_ref()?;
             ip_config.gateway6 = Some(gateway.parse().unwrap());
@@ -824,6 +1027,7 @@ fn routes_from_dbus(route_data: &OwnedValue) -> Option<Vec<IpRoute>> {
             destination,
             next_hop: None,
             metric: None,
+            table: None,
         };
         if let Some(next_hop) = route_map.get("next-hop") {
             let next_hop_str: &str = next_hop.downcast_ref()?;
@@ -833,11 +1037,46 @@ fn routes_from_dbus(route_data: &OwnedValue) -> Option<Vec<IpRoute>> {
             let metric: u32 = *metric.downcast_ref()?;
             new_route.metric = Some(metric);
         }
+        if let Some(table) = route_map.get("table") {
+            let table: u32 = *table.downcast_ref()?;
+            new_route.table = Some(table);
+        }
         routes.push(new_route)
     }
     Some(routes)
 }
 
+fn routing_rules_from_dbus(rules_data: &OwnedValue) -> Option<Vec<IpRoutingRule>> {
+    let rules_data = rules_data.downcast_ref::<zbus::zvariant::Array>()?;
+    let mut rules: Vec<IpRoutingRule> = vec![];
+    for rule in rules_data.get() {
+        let rule_dict = rule.downcast_ref::<zvariant::Dict>()?;
+        let rule_map = <HashMap<String, zvariant::Value<'_>>>::try_from(rule_dict.clone()).ok()?;
+        let priority: u32 = *rule_map.get("priority")?.downcast_ref()?;
+        let table: u32 = rule_map
+            .get("table")
+            .and_then(|t| t.downcast_ref().copied())
+            .unwrap_or_default();
+        rules.push(IpRoutingRule {
+            priority,
+            from: rule_network_from_dbus(&rule_map, "from"),
+            to: rule_network_from_dbus(&rule_map, "to"),
+            table,
+        })
+    }
+    Some(rules)
+}
+
+/// Reads the source ("from") or destination ("to") network of a routing rule.
+fn rule_network_from_dbus(
+    rule_map: &HashMap<String, zvariant::Value<'_>>,
+    key: &str,
+) -> Option<IpInet> {
+    let address: &str = rule_map.get(key)?.downcast_ref()?;
+    let len: u8 = *rule_map.get(&format!("{key}-len"))?.downcast_ref()?;
+    IpInet::new(address.parse().ok()?, len).ok()
+}
+
 fn nameservers_from_dbus(dns_data: &OwnedValue) -> Option<Vec<IpAddr>> {
     let dns_data = dns_data.downcast_ref::<zbus::zvariant::Array>()?;
     let mut servers: Vec<IpAddr> = vec![];
@@ -1006,7 +1245,10 @@ mod test {
     };
     use crate::network::{
         model::*,
-        nm::dbus::{BOND_KEY, ETHERNET_KEY, INFINIBAND_KEY, WIRELESS_KEY, WIRELESS_SECURITY_KEY},
+        nm::dbus::{
+            BOND_KEY, ETHERNET_KEY, IEEE_8021X_KEY, INFINIBAND_KEY, WIRELESS_KEY,
+            WIRELESS_SECURITY_KEY,
+        },
     };
     use agama_lib::network::types::{BondMode, SSID};
     use cidr::IpInet;
@@ -1032,6 +1274,15 @@ mod test {
             ("prefix".to_string(), Value::new(24_u32)),
             ("next-hop".to_string(), Value::new("192.168.0.1")),
             ("metric".to_string(), Value::new(100_u32)),
+            ("table".to_string(), Value::new(200_u32)),
+        ])];
+
+        let rules_v4_data = vec![HashMap::from([
+            ("family".to_string(), Value::new(2_i32)),
+            ("priority".to_string(), Value::new(1000_u32)),
+            ("from".to_string(), Value::new("192.168.0.0")),
+            ("from-len".to_string(), Value::new(24_u8)),
+            ("table".to_string(), Value::new(200_u32)),
         ])];
 
         let ipv4_section = HashMap::from([
@@ -1054,6 +1305,10 @@ mod test {
                 "route-data".to_string(),
                 Value::new(route_v4_data).to_owned(),
             ),
+            (
+                "routing-rules".to_string(),
+                Value::new(rules_v4_data).to_owned(),
+            ),
         ]);
 
         let address_v6_data = vec![HashMap::from([
@@ -1144,7 +1399,17 @@ mod test {
             Some(vec![IpRoute {
                 destination: IpInet::new("192.168.0.0".parse().unwrap(), 24_u8).unwrap(),
                 next_hop: Some(IpAddr::from_str("192.168.0.1").unwrap()),
-                metric: Some(100)
+                metric: Some(100),
+                table: Some(200)
+            }])
+        );
+        assert_eq!(
+            ip_config.routing_rules4,
+            Some(vec![IpRoutingRule {
+                priority: 1000,
+                from: Some(IpInet::new("192.168.0.0".parse().unwrap(), 24_u8).unwrap()),
+                to: None,
+                table: 200
             }])
         );
         assert_eq!(
@@ -1152,7 +1417,8 @@ mod test {
             Some(vec![IpRoute {
                 destination: IpInet::new("2001:db8::".parse().unwrap(), 64_u8).unwrap(),
                 next_hop: Some(IpAddr::from_str("2001:db8::1").unwrap()),
-                metric: Some(100)
+                metric: Some(100),
+                table: None
             }])
         );
     }
@@ -1307,6 +1573,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_connection_from_dbus_ieee_8021x() {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([
+            ("id".to_string(), Value::new("eth0").to_owned()),
+            ("uuid".to_string(), Value::new(uuid).to_owned()),
+        ]);
+
+        let ieee_8021x_section = HashMap::from([
+            ("eap".to_string(), Value::new(vec!["tls"]).to_owned()),
+            ("identity".to_string(), Value::new("agama").to_owned()),
+            (
+                "ca-cert".to_string(),
+                Value::new(b"file:///etc/pki/ca.pem\0".to_vec()).to_owned(),
+            ),
+            (
+                "client-cert".to_string(),
+                Value::new(b"-----BEGIN CERTIFICATE-----".to_vec()).to_owned(),
+            ),
+        ]);
+
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (ETHERNET_KEY.to_string(), HashMap::new()),
+            (IEEE_8021X_KEY.to_string(), ieee_8021x_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ieee_8021x = connection.ieee_8021x_config.unwrap();
+        assert_eq!(ieee_8021x.eap, vec![EAPMethod::Tls]);
+        assert_eq!(ieee_8021x.identity, Some("agama".to_string()));
+        assert_eq!(ieee_8021x.ca_cert, Some("/etc/pki/ca.pem".to_string()));
+        assert_eq!(
+            ieee_8021x.client_cert,
+            Some("-----BEGIN CERTIFICATE-----".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dbus_from_ieee_8021x_connection() {
+        let config = IEEE8021XConfig {
+            eap: vec![EAPMethod::Peap],
+            phase2_auth: Some(Phase2AuthMethod::Mschapv2),
+            identity: Some("agama".to_string()),
+            password: Some("nots3cr3t".to_string()),
+            ca_cert: Some("/etc/pki/ca.pem".to_string()),
+            ..Default::default()
+        };
+        let mut ethernet = build_base_connection();
+        ethernet.ieee_8021x_config = Some(config);
+        let ethernet_dbus = connection_to_dbus(&ethernet, None);
+
+        let ieee_8021x = ethernet_dbus.get(IEEE_8021X_KEY).unwrap();
+        let eap: &Array = ieee_8021x.get("eap").unwrap().downcast_ref().unwrap();
+        let eap: Vec<&str> = eap
+            .get()
+            .iter()
+            .map(|m| m.downcast_ref::<str>().unwrap())
+            .collect();
+        assert_eq!(eap, vec!["peap"]);
+        let phase2_auth: &str = ieee_8021x
+            .get("phase2-auth")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(phase2_auth, "mschapv2");
+        let password: &str = ieee_8021x.get("password").unwrap().downcast_ref().unwrap();
+        assert_eq!(password, "nots3cr3t");
+        let ca_cert = ieee_8021x.get("ca-cert").unwrap();
+        assert_eq!(*ca_cert, Value::new(b"file:///etc/pki/ca.pem\0".to_vec()));
+    }
+
     #[test]
     fn test_dbus_from_wireless_connection() {
         let config = WirelessConfig {
@@ -1532,6 +1870,56 @@ mod test {
         assert_eq!(ethernet.get("mtu"), Some(&Value::from(0_u32)));
     }
 
+    #[test]
+    fn test_merge_ieee_8021x_section() {
+        let mut original = OwnedNestedHash::new();
+        let connection = HashMap::from([
+            ("id".to_string(), Value::new("conn0".to_string()).to_owned()),
+            (
+                "type".to_string(),
+                Value::new(ETHERNET_KEY.to_string()).to_owned(),
+            ),
+        ]);
+        original.insert("connection".to_string(), connection);
+
+        let mut updated = Connection {
+            id: "conn0".to_string(),
+            ieee_8021x_config: Some(IEEE8021XConfig {
+                eap: vec![EAPMethod::Tls],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let updated_dbus = connection_to_dbus(&updated, None);
+        let merged = merge_dbus_connections(&original, &updated_dbus);
+        assert!(merged.contains_key(IEEE_8021X_KEY));
+
+        let ieee_8021x = HashMap::from([
+            ("eap".to_string(), Value::new(vec!["peap"]).to_owned()),
+            ("identity".to_string(), Value::new("alice").to_owned()),
+            ("password".to_string(), Value::new("s3cr3t").to_owned()),
+            ("phase1-peapver".to_string(), Value::new("0").to_owned()),
+        ]);
+        original.insert(IEEE_8021X_KEY.to_string(), ieee_8021x);
+        updated.ieee_8021x_config = Some(IEEE8021XConfig {
+            eap: vec![EAPMethod::Peap],
+            identity: Some("bob".to_string()),
+            ..Default::default()
+        });
+        let updated_dbus = connection_to_dbus(&updated, None);
+        let merged = merge_dbus_connections(&original, &updated_dbus);
+        let ieee_8021x = merged.get(IEEE_8021X_KEY).unwrap();
+        assert_eq!(ieee_8021x.get("identity"), Some(&Value::from("bob")));
+        // the secrets and the unknown properties are kept
+        assert_eq!(ieee_8021x.get("password"), Some(&Value::from("s3cr3t")));
+        assert_eq!(ieee_8021x.get("phase1-peapver"), Some(&Value::from("0")));
+
+        updated.ieee_8021x_config = None;
+        let updated_dbus = connection_to_dbus(&updated, None);
+        let merged = merge_dbus_connections(&original, &updated_dbus);
+        assert!(!merged.contains_key(IEEE_8021X_KEY));
+    }
+
     fn build_ethernet_section_from_dbus() -> HashMap<String, OwnedValue> {
         HashMap::from([
             ("auto-negotiate".to_string(), true.into()),
@@ -1556,11 +1944,19 @@ mod test {
                 destination: IpInet::new("192.168.0.0".parse().unwrap(), 24_u8).unwrap(),
                 next_hop: Some(IpAddr::from_str("192.168.0.1").unwrap()),
                 metric: Some(100),
+                table: Some(200),
+            }]),
+            routing_rules4: Some(vec![IpRoutingRule {
+                priority: 1000,
+                from: Some(IpInet::new("192.168.0.0".parse().unwrap(), 24_u8).unwrap()),
+                to: None,
+                table: 200,
             }]),
             routes6: Some(vec![IpRoute {
                 destination: IpInet::new("2001:db8::".parse().unwrap(), 64_u8).unwrap(),
                 next_hop: Some(IpAddr::from_str("2001:db8::1").unwrap()),
                 metric: Some(100),
+                table: None,
             }]),
             dns_searchlist: vec!["suse.com".to_string(), "suse.de".to_string()],
             ..Default::default()
@@ -1618,6 +2014,25 @@ mod test {
             assert_eq!(route4_hashmap["next-hop"], Value::from("192.168.0.1"));
             assert!(route4_hashmap.contains_key("metric"));
             assert_eq!(route4_hashmap["metric"], Value::from(100_u32));
+            assert_eq!(route4_hashmap["table"], Value::from(200_u32));
+        }
+        let rules4_array: Array = ipv4_dbus
+            .get("routing-rules")
+            .unwrap()
+            .downcast_ref::<Value>()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(rules4_array.len(), 1);
+        for rule4 in rules4_array.iter() {
+            let rule4_dict: Dict = rule4.downcast_ref::<Value>().unwrap().try_into().unwrap();
+            let rule4_hashmap: HashMap<String, Value> = rule4_dict.try_into().unwrap();
+            assert_eq!(rule4_hashmap["family"], Value::from(2_i32));
+            assert_eq!(rule4_hashmap["priority"], Value::from(1000_u32));
+            assert_eq!(rule4_hashmap["from"], Value::from("192.168.0.0"));
+            assert_eq!(rule4_hashmap["from-len"], Value::from(24_u8));
+            assert!(!rule4_hashmap.contains_key("to"));
+            assert_eq!(rule4_hashmap["table"], Value::from(200_u32));
         }
         let dns_searchlist_array: Array = ipv4_dbus
             .get("dns-search")
@@ -1661,7 +2076,9 @@ mod test {
             assert_eq!(route6_hashmap["next-hop"], Value::from("2001:db8::1"));
             assert!(route6_hashmap.contains_key("metric"));
             assert_eq!(route6_hashmap["metric"], Value::from(100_u32));
+            assert!(!route6_hashmap.contains_key("table"));
         }
+        assert!(!ipv6_dbus.contains_key("routing-rules"));
         let dns_searchlist_array: Array = ipv6_dbus
             .get("dns-search")
             .unwrap()
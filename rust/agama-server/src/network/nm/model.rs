@@ -82,8 +82,11 @@ impl TryFrom<NmDeviceType> for DeviceType {
         match value {
             NmDeviceType(1) => Ok(DeviceType::Ethernet),
             NmDeviceType(2) => Ok(DeviceType::Wireless),
+            NmDeviceType(9) => Ok(DeviceType::Infiniband),
             NmDeviceType(10) => Ok(DeviceType::Bond),
+            NmDeviceType(11) => Ok(DeviceType::Vlan),
             NmDeviceType(13) => Ok(DeviceType::Bridge),
+            NmDeviceType(16) => Ok(DeviceType::Tun),
             NmDeviceType(22) => Ok(DeviceType::Dummy),
             NmDeviceType(32) => Ok(DeviceType::Loopback),
             NmDeviceType(_) => Err(NmError::UnsupportedDeviceType(value.into())),
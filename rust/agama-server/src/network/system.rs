@@ -182,6 +182,22 @@ impl NetworkSystemClient {
         Ok(result?)
     }
 
+    /// Sets the ports of a controller connection.
+    ///
+    /// * `uuid`: controller UUID.
+    /// * `ports`: IDs or interface names of the ports.
+    pub async fn set_ports(
+        &self,
+        uuid: Uuid,
+        ports: Vec<String>,
+    ) -> Result<(), NetworkSystemError> {
+        let (tx, rx) = oneshot::channel();
+        self.actions
+            .send(Action::SetPorts(uuid, Box::new(ports), tx))?;
+        let result = rx.await?;
+        Ok(result?)
+    }
+
     /// Removes the connection with the given ID.
     ///
     /// * `id`: Connection ID.
@@ -15,7 +15,7 @@ use axum::{
 
 use super::{
     error::NetworkStateError,
-    model::{AccessPoint, GeneralState},
+    model::{AccessPoint, ConnectionConfig, GeneralState},
     system::{NetworkSystemClient, NetworkSystemError},
     Adapter,
 };
@@ -193,8 +193,8 @@ async fn connections(
     let connections = state.network.get_connections().await?;
     let connections = connections
         .iter()
-        .map(|c| NetworkConnection::try_from(c.clone()).unwrap())
-        .collect();
+        .map(|c| to_network_connection(c.clone(), &connections))
+        .collect::<Result<Vec<_>, _>>()?;
 
     Ok(Json(connections))
 }
@@ -211,16 +211,45 @@ async fn add_connection(
     State(state): State<NetworkServiceState>,
     Json(conn): Json<NetworkConnection>,
 ) -> Result<Json<Connection>, NetworkError> {
-    let conn = Connection::try_from(conn)?;
+    let ports = conn.ports().map(|p| p.to_vec());
+    let conn = from_network_connection(&state, conn).await?;
     let id = conn.id.clone();
+    let uuid = conn.uuid;
 
+    // do not leave a half-configured controller behind
+    if let Some(ports) = &ports {
+        check_ports(&state, ports).await?;
+    }
     state.network.add_connection(conn).await?;
+    if let Some(ports) = ports {
+        state.network.set_ports(uuid, ports).await?;
+    }
     match state.network.get_connection(&id).await? {
         None => Err(NetworkError::CannotAddConnection(id.clone())),
         Some(conn) => Ok(Json(conn)),
     }
 }
 
+/// Checks whether the given ports exist, either as connections (ID or interface name) or as
+/// devices.
+///
+/// * `state`: network service state.
+/// * `ports`: ports to check.
+async fn check_ports(state: &NetworkServiceState, ports: &[String]) -> Result<(), NetworkError> {
+    let connections = state.network.get_connections().await?;
+    let devices = state.network.get_devices().await?;
+    for port in ports {
+        let known = connections
+            .iter()
+            .any(|c| c.id == *port || c.interface.as_ref() == Some(port))
+            || devices.iter().any(|d| d.name == *port);
+        if !known {
+            return Err(NetworkStateError::UnknownPort(port.to_string()).into());
+        }
+    }
+    Ok(())
+}
+
 #[utoipa::path(
     get,
     path = "/network/connections/:id",
@@ -238,7 +267,8 @@ async fn connection(
         .await?
         .ok_or_else(|| NetworkError::UnknownConnection(id.clone()))?;
 
-    let conn = NetworkConnection::try_from(conn)?;
+    let connections = state.network.get_connections().await?;
+    let conn = to_network_connection(conn, &connections)?;
 
     Ok(Json(conn))
 }
@@ -280,7 +310,8 @@ async fn update_connection(
         .get_connection(&id)
         .await?
         .ok_or_else(|| NetworkError::UnknownConnection(id.clone()))?;
-    let mut conn = Connection::try_from(conn)?;
+    let ports = conn.ports().map(|p| p.to_vec());
+    let mut conn = from_network_connection(&state, conn).await?;
     if orig_conn.id != id {
         // FIXME: why?
         return Err(NetworkError::UnknownConnection(id));
@@ -288,7 +319,28 @@ async fn update_connection(
         conn.uuid = orig_conn.uuid;
     }
 
+    // keep the routes and rules that are not given
+    let ip_config = &mut conn.ip_config;
+    let orig_ip_config = orig_conn.ip_config;
+    ip_config.routes4 = ip_config.routes4.take().or(orig_ip_config.routes4);
+    ip_config.routes6 = ip_config.routes6.take().or(orig_ip_config.routes6);
+    ip_config.routing_rules4 = ip_config
+        .routing_rules4
+        .take()
+        .or(orig_ip_config.routing_rules4);
+    ip_config.routing_rules6 = ip_config
+        .routing_rules6
+        .take()
+        .or(orig_ip_config.routing_rules6);
+
+    let uuid = conn.uuid;
+    if let Some(ports) = &ports {
+        check_ports(&state, ports).await?;
+    }
     state.network.update_connection(conn).await?;
+    if let Some(ports) = ports {
+        state.network.set_ports(uuid, ports).await?;
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -375,3 +427,63 @@ async fn apply(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Converts a connection into its settings, setting the parent from the controller and, for
+/// bonds and bridges, the ports.
+///
+/// * `conn`: connection to convert.
+/// * `connections`: known connections, used to find the controller and the ports.
+fn to_network_connection(
+    conn: Connection,
+    connections: &[Connection],
+) -> Result<NetworkConnection, NetworkError> {
+    let uuid = conn.uuid;
+    let controller = conn.controller;
+    let mut network_conn = NetworkConnection::try_from(conn)?;
+    network_conn.parent = controller
+        .and_then(|uuid| connections.iter().find(|c| c.uuid == uuid))
+        .map(|c| c.id.clone());
+
+    let ports = connections
+        .iter()
+        .filter(|c| c.controller == Some(uuid) && !c.is_removed())
+        .map(|c| c.interface.as_deref().unwrap_or(&c.id).to_string())
+        .collect();
+    if let Some(bond) = network_conn.bond.as_mut() {
+        bond.ports = Some(ports);
+    } else if let Some(bridge) = network_conn.bridge.as_mut() {
+        bridge.ports = Some(ports);
+    }
+    Ok(network_conn)
+}
+
+/// Converts the connection settings into a connection, setting the controller from the parent.
+///
+/// The parent can be referenced by its connection ID or by its interface name.
+///
+/// * `state`: network service state.
+/// * `conn`: connection settings to convert.
+async fn from_network_connection(
+    state: &NetworkServiceState,
+    conn: NetworkConnection,
+) -> Result<Connection, NetworkError> {
+    let parent = conn.parent.clone();
+    let mut connection = Connection::try_from(conn)?;
+    let Some(parent) = parent else {
+        return Ok(connection);
+    };
+
+    let connections = state.network.get_connections().await?;
+    let controller = connections
+        .iter()
+        .find(|c| c.id == parent || c.interface.as_deref() == Some(parent.as_str()))
+        .ok_or_else(|| NetworkStateError::UnknownConnection(parent.clone()))?;
+    if !matches!(
+        controller.config,
+        ConnectionConfig::Bond(_) | ConnectionConfig::Bridge(_)
+    ) {
+        return Err(NetworkStateError::NotControllerConnection(parent).into());
+    }
+    connection.controller = Some(controller.uuid);
+    Ok(connection)
+}
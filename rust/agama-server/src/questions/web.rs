@@ -5,29 +5,39 @@
 //! * `questions_service` which returns the Axum service.
 //! * `questions_stream` which offers an stream that emits questions related signals.
 
-use crate::{error::Error, web::Event};
+use crate::{
+    dbus::{DBusObjectChange, DBusObjectChangesStream},
+    error::Error,
+    web::Event,
+};
 use agama_lib::{
-    dbus::{extract_id_from_path, get_property},
+    dbus::{extract_id_from_path, get_property, to_owned_hash},
     error::ServiceError,
     proxies::{GenericQuestionProxy, QuestionWithPasswordProxy, Questions1Proxy},
-    questions::model::{Answer, GenericQuestion, PasswordAnswer, Question, QuestionWithPassword},
+    questions::model::{
+        Answer, GenericQuestion, PasswordAnswer, Question, QuestionWithPassword, QuestionsMode,
+    },
 };
 use anyhow::Context;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{delete, get},
+    routing::{delete, get, post},
     Json, Router,
 };
-use std::{collections::HashMap, pin::Pin};
+use std::{collections::HashMap, io::Write, pin::Pin};
 use tokio_stream::{Stream, StreamExt};
 use zbus::{
-    fdo::ObjectManagerProxy,
+    fdo::{InterfacesAdded, InterfacesRemoved, ObjectManagerProxy},
     names::{InterfaceName, OwnedInterfaceName},
     zvariant::{ObjectPath, OwnedObjectPath, OwnedValue},
 };
 
+const QUESTIONS_PATH: &str = "/org/opensuse/Agama1/Questions";
+const GENERIC_INTERFACE: &str = "org.opensuse.Agama1.Questions.Generic";
+const WITH_PASSWORD_INTERFACE: &str = "org.opensuse.Agama1.Questions.WithPassword";
+
 // TODO: move to lib or maybe not and just have in lib client for http API?
 #[derive(Clone)]
 struct QuestionsClient<'a> {
@@ -116,7 +126,7 @@ impl<'a> QuestionsClient<'a> {
             if !answer.is_empty() {
                 continue;
             }
-            let mut question = self.build_generic_question(generic_properties)?;
+            let mut question = build_generic_question(generic_properties)?;
 
             if interfaces_hash.contains_key(&self.with_password_interface) {
                 question.with_password = Some(QuestionWithPassword {});
@@ -127,25 +137,6 @@ impl<'a> QuestionsClient<'a> {
         Ok(result)
     }
 
-    fn build_generic_question(
-        &self,
-        properties: &HashMap<String, OwnedValue>,
-    ) -> Result<Question, ServiceError> {
-        let result = Question {
-            generic: GenericQuestion {
-                id: Some(get_property(properties, "Id")?),
-                class: get_property(properties, "Class")?,
-                text: get_property(properties, "Text")?,
-                options: get_property(properties, "Options")?,
-                default_option: get_property(properties, "DefaultOption")?,
-                data: get_property(properties, "Data")?,
-            },
-            with_password: None,
-        };
-
-        Ok(result)
-    }
-
     pub async fn delete(&self, id: u32) -> Result<(), ServiceError> {
         let question_path = ObjectPath::from(
             ObjectPath::try_from(format!("/org/opensuse/Agama1/Questions/{}", id))
@@ -219,6 +210,57 @@ impl<'a> QuestionsClient<'a> {
             .await?;
         Ok(())
     }
+
+    pub async fn mode(&self) -> Result<QuestionsMode, ServiceError> {
+        let interactive = self.questions_proxy.interactive().await?;
+        Ok(QuestionsMode { interactive })
+    }
+
+    pub async fn set_mode(&self, mode: QuestionsMode) -> Result<(), ServiceError> {
+        Ok(self
+            .questions_proxy
+            .set_interactive(mode.interactive)
+            .await?)
+    }
+
+    /// Adds a set of predefined answers.
+    ///
+    /// The questions service only reads the answers from a file, so they are written to a
+    /// temporary one. JSON is valid YAML, so there is no need to convert them.
+    ///
+    /// * `answers`: answers document (see the questions documentation for the format).
+    pub async fn add_answers(&self, answers: serde_json::Value) -> Result<(), ServiceError> {
+        let mut file = tempfile::Builder::new()
+            .prefix("agama-answers")
+            .suffix(".yaml")
+            .tempfile()
+            .context("Could not create the answers file")?;
+        file.write_all(answers.to_string().as_bytes())
+            .context("Could not write the answers file")?;
+        let path = file.path().to_string_lossy();
+        Ok(self.questions_proxy.add_answer_file(&path).await?)
+    }
+}
+
+/// Builds a question from the properties of the generic question D-Bus interface.
+///
+/// * `properties`: D-Bus properties.
+fn build_generic_question(
+    properties: &HashMap<String, OwnedValue>,
+) -> Result<Question, ServiceError> {
+    let result = Question {
+        generic: GenericQuestion {
+            id: Some(get_property(properties, "Id")?),
+            class: get_property(properties, "Class")?,
+            text: get_property(properties, "Text")?,
+            options: get_property(properties, "Options")?,
+            default_option: get_property(properties, "DefaultOption")?,
+            data: get_property(properties, "Data")?,
+        },
+        with_password: None,
+    };
+
+    Ok(result)
 }
 
 #[derive(Clone)]
@@ -232,6 +274,8 @@ pub async fn questions_service(dbus: zbus::Connection) -> Result<Router, Service
     let state = QuestionsState { questions };
     let router = Router::new()
         .route("/", get(list_questions).post(create_question))
+        .route("/mode", get(get_mode).put(set_mode))
+        .route("/answers", post(add_answers))
         .route("/:id", delete(delete_question))
         .route("/:id/answer", get(get_answer).put(answer_question))
         .with_state(state);
@@ -242,8 +286,7 @@ pub async fn questions_stream(
     dbus: zbus::Connection,
 ) -> Result<Pin<Box<dyn Stream<Item = Event> + Send>>, Error> {
     let question_path = OwnedObjectPath::from(
-        ObjectPath::try_from("/org/opensuse/Agama1/Questions")
-            .context("failed to create object path")?,
+        ObjectPath::try_from(QUESTIONS_PATH).context("failed to create object path")?,
     );
     let proxy = ObjectManagerProxy::builder(&dbus)
         .path(question_path)
@@ -255,15 +298,79 @@ pub async fn questions_stream(
     let add_stream = proxy
         .receive_interfaces_added()
         .await?
-        .then(|_| async move { Event::QuestionsChanged });
+        .map(|signal| question_added(signal).unwrap_or_default());
     let remove_stream = proxy
         .receive_interfaces_removed()
         .await?
-        .then(|_| async move { Event::QuestionsChanged });
-    let stream = StreamExt::merge(add_stream, remove_stream);
+        .map(|signal| question_removed(signal).into_iter().collect());
+    // QuestionsChanged is still emitted for the clients that just reload the list of questions
+    let changes = StreamExt::merge(add_stream, remove_stream).map(|mut events: Vec<Event>| {
+        events.push(Event::QuestionsChanged);
+        futures_util::stream::iter(events)
+    });
+    let changes = futures_util::StreamExt::flatten(changes);
+
+    let path = ObjectPath::from_static_str(QUESTIONS_PATH).context("Invalid questions path")?;
+    let answer_stream = DBusObjectChangesStream::new(&dbus, &path, &path, GENERIC_INTERFACE)
+        .await?
+        .filter_map(question_answered);
+    let stream = StreamExt::merge(changes, answer_stream);
     Ok(Box::pin(stream))
 }
 
+/// Returns the events for a new question.
+///
+/// If the question was answered automatically, it includes a [Event::QuestionAnswered] too.
+///
+/// * `signal`: InterfacesAdded signal.
+fn question_added(signal: InterfacesAdded) -> Option<Vec<Event>> {
+    let args = signal.args().ok()?;
+    let interfaces = &args.interfaces_and_properties;
+    let properties = to_owned_hash(interfaces.get(GENERIC_INTERFACE)?);
+    let mut question = build_generic_question(&properties).ok()?;
+    if interfaces.contains_key(WITH_PASSWORD_INTERFACE) {
+        question.with_password = Some(QuestionWithPassword {});
+    }
+
+    let id = question.generic.id?;
+    let mut events = vec![Event::QuestionAdded { question }];
+    let answer: String = get_property(&properties, "Answer").ok()?;
+    if !answer.is_empty() {
+        events.push(Event::QuestionAnswered { id, answer });
+    }
+    Some(events)
+}
+
+/// Returns the event for a removed question.
+///
+/// * `signal`: InterfacesRemoved signal.
+fn question_removed(signal: InterfacesRemoved) -> Option<Event> {
+    let args = signal.args().ok()?;
+    if !args.interfaces.contains(&GENERIC_INTERFACE) {
+        return None;
+    }
+    let path = OwnedObjectPath::from(args.object_path().clone());
+    let id = extract_id_from_path(&path).ok()?;
+    Some(Event::QuestionRemoved { id })
+}
+
+/// Returns the event for an answered question.
+///
+/// The password (if any) is never included.
+///
+/// * `change`: change in the generic question D-Bus interface.
+fn question_answered(change: DBusObjectChange) -> Option<Event> {
+    let DBusObjectChange::Changed(path, properties) = change else {
+        return None;
+    };
+    let answer: String = get_property(&properties, "Answer").ok()?;
+    if answer.is_empty() {
+        return None;
+    }
+    let id = extract_id_from_path(&path).ok()?;
+    Some(Event::QuestionAnswered { id, answer })
+}
+
 /// Returns the list of questions that waits for answer.
 ///
 /// * `state`: service state.
@@ -277,6 +384,47 @@ async fn list_questions(
     Ok(Json(state.questions.questions().await?))
 }
 
+/// Returns how the questions are answered.
+///
+/// * `state`: service state.
+#[utoipa::path(get, path = "/questions/mode", responses(
+    (status = 200, description = "Questions mode", body = QuestionsMode),
+    (status = 400, description = "The D-Bus service could not perform the action")
+))]
+async fn get_mode(State(state): State<QuestionsState<'_>>) -> Result<Json<QuestionsMode>, Error> {
+    Ok(Json(state.questions.mode().await?))
+}
+
+/// Sets how the questions are answered.
+///
+/// * `state`: service state.
+/// * `mode`: new questions mode.
+#[utoipa::path(put, path = "/questions/mode", responses(
+    (status = 200, description = "The questions mode was changed"),
+    (status = 400, description = "The D-Bus service could not perform the action")
+))]
+async fn set_mode(
+    State(state): State<QuestionsState<'_>>,
+    Json(mode): Json<QuestionsMode>,
+) -> Result<(), Error> {
+    Ok(state.questions.set_mode(mode).await?)
+}
+
+/// Adds a set of predefined answers.
+///
+/// * `state`: service state.
+/// * `answers`: answers document, with the same format as the answers file.
+#[utoipa::path(post, path = "/questions/answers", responses(
+    (status = 200, description = "The answers were added"),
+    (status = 400, description = "The answers are not valid")
+))]
+async fn add_answers(
+    State(state): State<QuestionsState<'_>>,
+    Json(answers): Json<serde_json::Value>,
+) -> Result<(), Error> {
+    Ok(state.questions.add_answers(answers).await?)
+}
+
 /// Get answer to question.
 ///
 /// * `state`: service state.
@@ -347,3 +495,33 @@ async fn create_question(
     let res = state.questions.create_question(question).await?;
     Ok(Json(res))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::question_answered;
+    use crate::{dbus::DBusObjectChange, web::Event};
+    use std::collections::HashMap;
+    use zbus::zvariant::{ObjectPath, OwnedObjectPath, Value};
+
+    fn answer_change(answer: &str) -> DBusObjectChange {
+        let path = ObjectPath::from_static_str("/org/opensuse/Agama1/Questions/3").unwrap();
+        let properties = HashMap::from([("Answer".to_string(), Value::from(answer).into())]);
+        DBusObjectChange::Changed(OwnedObjectPath::from(path), properties)
+    }
+
+    #[test]
+    fn test_question_answered() {
+        let event = question_answered(answer_change("yes")).unwrap();
+        let json = serde_json::to_value(event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "QuestionAnswered", "id": 3, "answer": "yes" })
+        );
+
+        assert!(question_answered(answer_change("")).is_none());
+        assert!(matches!(
+            question_answered(answer_change("no")),
+            Some(Event::QuestionAnswered { id: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod web;
+pub use web::settings_service;
@@ -0,0 +1,105 @@
+//! This module implements the web API for the whole installation settings.
+//!
+//! It allows reading or applying a complete profile in a single request. Under the hood, it relies
+//! on [agama_lib::Store], which talks to the rest of the API, so the settings are read and written
+//! in the same order as `agama config` always did. The store uses the caller's token, so it is
+//! subject to the same scope checks as the caller.
+
+use crate::error::Error;
+use agama_lib::{
+    auth::AuthToken, base_http_client::BaseHTTPClient, error::ServiceError,
+    install_settings::InstallSettings, Store,
+};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+
+#[derive(Clone)]
+struct SettingsState {
+    client: BaseHTTPClient,
+}
+
+impl SettingsState {
+    /// Builds a store to read/write the settings through the API.
+    ///
+    /// * `token`: caller's token.
+    async fn store(&self, token: &AuthToken) -> Result<Store, ServiceError> {
+        let client = self.client.clone().with_token(token)?;
+        Store::new(client).await
+    }
+}
+
+/// Sets up and returns the axum service for the installation settings.
+///
+/// * `client`: HTTP client pointing to the API of this server.
+pub async fn settings_service(client: BaseHTTPClient) -> Result<Router, ServiceError> {
+    let state = SettingsState { client };
+    let router = Router::new()
+        .route("/", get(get_config).put(set_config))
+        .with_state(state);
+    Ok(router)
+}
+
+/// Returns the complete installation settings.
+///
+/// The settings are expressed according to the profile JSON schema.
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    operation_id = "get_install_settings",
+    responses(
+        (status = 200, description = "Installation settings"),
+        (status = 400, description = "The settings could not be read")
+    )
+)]
+async fn get_config(
+    State(state): State<SettingsState>,
+    Extension(token): Extension<AuthToken>,
+) -> Result<Json<InstallSettings>, Error> {
+    let store = state.store(&token).await?;
+    Ok(Json(store.load().await?))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct SetConfigQuery {
+    /// Whether to restore the previous settings if any section cannot be applied.
+    #[serde(default)]
+    atomic: bool,
+}
+
+/// Applies the given installation settings.
+///
+/// The sections are applied in the same order as [agama_lib::Store] does: network, product,
+/// localization, software, users and storage.
+///
+/// * `state`: service state.
+/// * `token`: caller's token.
+/// * `query`: query parameters.
+/// * `settings`: installation settings according to the profile JSON schema.
+#[utoipa::path(
+    put,
+    path = "/api/config",
+    operation_id = "set_install_settings",
+    params(SetConfigQuery),
+    responses(
+        (status = 200, description = "The settings were applied"),
+        (status = 400, description = "The settings could not be applied")
+    )
+)]
+async fn set_config(
+    State(state): State<SettingsState>,
+    Extension(token): Extension<AuthToken>,
+    Query(query): Query<SetConfigQuery>,
+    Json(settings): Json<InstallSettings>,
+) -> Result<Json<()>, Error> {
+    let store = state.store(&token).await?;
+    if query.atomic {
+        store.store_atomic(&settings).await?;
+    } else {
+        store.store(&settings).await?;
+    }
+    Ok(Json(()))
+}
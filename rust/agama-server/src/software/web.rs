@@ -14,7 +14,11 @@ use crate::{
 };
 use agama_lib::{
     error::ServiceError,
-    product::{proxies::RegistrationProxy, Product, ProductClient, RegistrationRequirement},
+    product::{
+        model::{FailureDetails, RegistrationInfo, RegistrationParams},
+        proxies::RegistrationProxy,
+        Product, ProductClient,
+    },
     software::{
         model::SoftwareConfig,
         proxies::{Software1Proxy, SoftwareProductProxy},
@@ -28,7 +32,7 @@ use axum::{
     routing::{get, post, put},
     Json, Router,
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::collections::HashMap;
 use tokio_stream::{Stream, StreamExt};
 
@@ -229,19 +233,6 @@ async fn products(State(state): State<SoftwareState<'_>>) -> Result<Json<Vec<Pro
     Ok(Json(products))
 }
 
-/// Information about registration configuration (product, patterns, etc.).
-#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct RegistrationInfo {
-    /// Registration key. Empty value mean key not used or not registered.
-    key: String,
-    /// Registration email. Empty value mean email not used or not registered.
-    email: String,
-    /// if registration is required, optional or not needed for current product.
-    /// Change only if selected product is changed.
-    requirement: RegistrationRequirement,
-}
-
 /// returns registration info
 ///
 /// * `state`: service state.
@@ -265,22 +256,6 @@ async fn get_registration(
     Ok(Json(result))
 }
 
-/// Software service configuration (product, patterns, etc.).
-#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
-pub struct RegistrationParams {
-    /// Registration key.
-    key: String,
-    /// Registration email.
-    email: String,
-}
-
-#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
-pub struct FailureDetails {
-    /// ID of error. See dbus API for possible values
-    id: u32,
-    /// human readable error string intended to be displayed to user
-    message: String,
-}
 /// Register product
 ///
 /// * `state`: service state.
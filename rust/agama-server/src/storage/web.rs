@@ -10,7 +10,7 @@ use agama_lib::{
     storage::{
         model::{Action, Device, DeviceSid, ProposalSettings, ProposalSettingsPatch, Volume},
         proxies::Storage1Proxy,
-        StorageClient,
+        StorageClient, StorageSettings,
     },
 };
 use axum::{
@@ -87,6 +87,7 @@ pub async fn storage_service(dbus: zbus::Connection) -> Result<Router, ServiceEr
     let client = StorageClient::new(dbus.clone()).await?;
     let state = StorageState { client };
     let router = Router::new()
+        .route("/config", get(get_config).put(set_config))
         .route("/probe", post(probe))
         .route("/devices/dirty", get(devices_dirty))
         .route("/devices/system", get(system_devices))
@@ -109,6 +110,45 @@ pub async fn storage_service(dbus: zbus::Connection) -> Result<Router, ServiceEr
     Ok(router)
 }
 
+/// Returns the storage configuration.
+///
+/// The configuration is expressed according to the storage section of the profile JSON schema.
+#[utoipa::path(
+    get,
+    path = "/config",
+    context_path = "/api/storage",
+    operation_id = "get_storage_config",
+    responses(
+        (status = 200, description = "Storage configuration"),
+        (status = 400, description = "The D-Bus service could not perform the action")
+    )
+)]
+async fn get_config(State(state): State<StorageState<'_>>) -> Result<Json<StorageSettings>, Error> {
+    Ok(Json(state.client.get_config().await?))
+}
+
+/// Sets the storage configuration.
+///
+/// * `state`: service state.
+/// * `config`: storage configuration according to the profile JSON schema.
+#[utoipa::path(
+    put,
+    path = "/config",
+    context_path = "/api/storage",
+    operation_id = "set_storage_config",
+    responses(
+        (status = 200, description = "Set the storage configuration"),
+        (status = 400, description = "The D-Bus service could not perform the action")
+    )
+)]
+async fn set_config(
+    State(state): State<StorageState<'_>>,
+    Json(settings): Json<StorageSettings>,
+) -> Result<Json<()>, Error> {
+    let _status: u32 = state.client.set_config(settings).await?;
+    Ok(Json(()))
+}
+
 /// Probes the storage devices.
 #[utoipa::path(
     post,
@@ -1,7 +1,7 @@
 //! This module implements a web-based API for Agama. It is responsible for:
 //!
 //! * Exposing an HTTP API to interact with Agama.
-//! * Emit relevant events via websocket.
+//! * Emit relevant events via websocket (or Server-Sent Events).
 //! * Serve the code for the web user interface (not implemented yet).
 
 use crate::{
@@ -10,6 +10,7 @@ use crate::{
     manager::web::{manager_service, manager_stream},
     network::{web::network_service, NetworkManagerAdapter},
     questions::web::{questions_service, questions_stream},
+    settings::web::settings_service,
     software::web::{software_service, software_streams},
     storage::web::{storage_service, storage_streams},
     users::web::{users_service, users_streams},
@@ -22,15 +23,23 @@ pub mod common;
 mod config;
 mod docs;
 mod event;
+mod health;
+mod history;
 mod http;
+mod metrics;
 mod service;
+mod sse;
 mod state;
+mod throttle;
 mod ws;
 
-use agama_lib::{connection, error::ServiceError};
+use agama_lib::{
+    auth::TokenScope, base_http_client::BaseHTTPClient, connection, error::ServiceError,
+};
 pub use config::ServiceConfig;
 pub use docs::ApiDoc;
 pub use event::{Event, EventsReceiver, EventsSender};
+use health::health_service;
 pub use service::MainServiceBuilder;
 use std::path::Path;
 use tokio_stream::{StreamExt, StreamMap};
@@ -40,11 +49,14 @@ use tokio_stream::{StreamExt, StreamMap};
 /// * `config`: service configuration.
 /// * `events`: channel to send the events through the WebSocket.
 /// * `dbus`: D-Bus connection.
+/// * `local_api`: HTTP client to access this API from the server itself (not authenticated). It
+///   should point to a loopback address, which is allowed to use plain HTTP.
 /// * `web_ui_dir`: public directory containing the web UI.
 pub async fn service<P>(
     config: ServiceConfig,
     events: EventsSender,
     dbus: zbus::Connection,
+    local_api: BaseHTTPClient,
     web_ui_dir: P,
 ) -> Result<Router, ServiceError>
 where
@@ -55,6 +67,7 @@ where
         .expect("Could not connect to NetworkManager to read the configuration");
 
     let router = MainServiceBuilder::new(events.clone(), web_ui_dir)
+        .add_public_service("/health", health_service(dbus.clone()).await?)
         .add_service("/l10n", l10n_service(dbus.clone(), events.clone()).await?)
         .add_service("/manager", manager_service(dbus.clone()).await?)
         .add_service("/software", software_service(dbus.clone()).await?)
@@ -62,6 +75,9 @@ where
         .add_service("/network", network_service(network_adapter, events).await?)
         .add_service("/questions", questions_service(dbus.clone()).await?)
         .add_service("/users", users_service(dbus.clone()).await?)
+        .add_service("/config", settings_service(local_api).await?)
+        .require_scope("/manager/install", TokenScope::Install)
+        .require_scope("/manager/finish", TokenScope::Install)
         .with_config(config)
         .build();
     Ok(router)
@@ -1,11 +1,12 @@
 //! Contains the code to handle access authorization.
 
 use super::state::ServiceState;
-use agama_lib::auth::{AuthToken, AuthTokenError, TokenClaims};
+use agama_lib::auth::{AuthToken, AuthTokenError, TokenClaims, TokenScope};
 use async_trait::async_trait;
 use axum::{
-    extract::FromRequestParts,
-    http::{request, StatusCode},
+    extract::{FromRequestParts, Request, State},
+    http::{header, request, Method, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json, RequestPartsExt,
 };
@@ -13,8 +14,13 @@ use axum_extra::{
     headers::{self, authorization::Bearer},
     TypedHeader,
 };
+use chrono::Utc;
 use pam::PamError;
 use serde_json::json;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 use thiserror::Error;
 
 /// Represents an authentication error.
@@ -26,9 +32,22 @@ pub enum AuthError {
     /// The authentication error is invalid.
     #[error("Invalid authentication token: {0}")]
     InvalidToken(#[from] AuthTokenError),
+    /// The authentication token has been revoked (e.g., the user logged out).
+    #[error("The authentication token has been revoked")]
+    RevokedToken,
+    /// The scope of the authentication token does not allow the operation.
+    #[error("The authentication token does not allow this operation (required scope: {0})")]
+    InsufficientScope(TokenScope),
+    /// The operation is reserved to tokens without restrictions (e.g., obtained with the root
+    /// password).
+    #[error("This operation requires an unrestricted authentication token")]
+    UnrestrictedTokenRequired,
     /// The authentication failed (most probably the password is wrong)
     #[error("Authentication via PAM failed: {0}")]
     Failed(#[from] PamError),
+    /// Too many failed login attempts. The client must wait the given amount of seconds.
+    #[error("Too many failed login attempts. Please, try again in {0} seconds")]
+    TooManyAttempts(u64),
 }
 
 impl IntoResponse for AuthError {
@@ -36,7 +55,18 @@ impl IntoResponse for AuthError {
         let body = json!({
             "error": self.to_string()
         });
-        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+        match self {
+            Self::TooManyAttempts(seconds) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, seconds.to_string())],
+                Json(body),
+            )
+                .into_response(),
+            Self::InsufficientScope(_) | Self::UnrestrictedTokenRequired => {
+                (StatusCode::FORBIDDEN, Json(body)).into_response()
+            }
+            _ => (StatusCode::BAD_REQUEST, Json(body)).into_response(),
+        }
     }
 }
 
@@ -66,6 +96,106 @@ impl FromRequestParts<ServiceState> for TokenClaims {
         };
 
         let token = AuthToken::new(&token);
-        Ok(token.claims(&state.config.jwt_secret)?)
+        let claims = token.claims(&state.config.jwt_secret)?;
+        if state.revoked_tokens.contains(&claims) {
+            return Err(AuthError::RevokedToken);
+        }
+
+        if let Some(RequiredScope(required)) = parts.extensions.get::<RequiredScope>() {
+            if !claims.allows(*required) {
+                return Err(AuthError::InsufficientScope(*required));
+            }
+        }
+
+        // keep the token, so the handlers can act on behalf of the caller
+        parts.extensions.insert(token);
+        Ok(claims)
+    }
+}
+
+/// Scope required to access a route.
+///
+/// It is added to the request extensions by [require_scope] and it is checked when extracting the
+/// [TokenClaims].
+#[derive(Clone, Copy, Debug)]
+pub struct RequiredScope(pub TokenScope);
+
+/// Scopes required to access specific routes.
+///
+/// Unless a route is included, reading (`GET` and `HEAD`) requires the `read` scope and any other
+/// method requires the `configure` scope.
+#[derive(Clone, Debug, Default)]
+pub struct ScopeRules(Vec<(String, TokenScope)>);
+
+impl ScopeRules {
+    /// Requires the given scope to access the routes under the given path.
+    ///
+    /// * `path`: path of the route (e.g., "/manager/install").
+    /// * `scope`: required scope.
+    pub fn add(&mut self, path: &str, scope: TokenScope) {
+        self.0.push((path.trim_end_matches('/').to_string(), scope));
+    }
+
+    /// Returns the scope required to access the given route.
+    ///
+    /// * `method`: HTTP method.
+    /// * `path`: path of the route.
+    pub fn required_scope(&self, method: &Method, path: &str) -> TokenScope {
+        let rule = self.0.iter().find(|(prefix, _)| {
+            path.strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        });
+
+        if let Some((_, scope)) = rule {
+            return *scope;
+        }
+
+        if method == Method::GET || method == Method::HEAD {
+            TokenScope::Read
+        } else {
+            TokenScope::Configure
+        }
+    }
+}
+
+/// Middleware that adds the [RequiredScope] to the request.
+///
+/// * `rules`: scopes required to access specific routes.
+/// * `request`: HTTP request.
+/// * `next`: next layer.
+pub async fn require_scope(
+    State(rules): State<ScopeRules>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let scope = rules.required_scope(request.method(), request.uri().path());
+    request.extensions_mut().insert(RequiredScope(scope));
+    next.run(request).await
+}
+
+/// Keeps the list of revoked tokens.
+///
+/// The tokens are identified by their `jti` claim. They are kept only until they expire, because
+/// an expired token is rejected anyway.
+#[derive(Clone, Default)]
+pub struct RevokedTokens(Arc<Mutex<HashMap<String, i64>>>);
+
+impl RevokedTokens {
+    /// Revokes the token with the given claims.
+    ///
+    /// * `claims`: claims of the token to revoke.
+    pub fn revoke(&self, claims: &TokenClaims) {
+        let mut tokens = self.0.lock().unwrap();
+        let now = Utc::now().timestamp();
+        tokens.retain(|_, exp| *exp >= now);
+        tokens.insert(claims.jti.clone(), claims.exp);
+    }
+
+    /// Determines whether the token with the given claims was revoked.
+    ///
+    /// * `claims`: claims of the token to check.
+    pub fn contains(&self, claims: &TokenClaims) -> bool {
+        let tokens = self.0.lock().unwrap();
+        tokens.contains_key(&claims.jti)
     }
 }
@@ -4,6 +4,7 @@ use std::{pin::Pin, task::Poll};
 
 use agama_lib::{
     error::ServiceError,
+    issue::Issue,
     progress::Progress,
     proxies::{IssuesProxy, ProgressProxy, ServiceStatusProxy},
 };
@@ -299,33 +300,6 @@ struct IssuesState<'a> {
     proxy: IssuesProxy<'a>,
 }
 
-#[derive(Clone, Debug, Serialize)]
-pub struct Issue {
-    description: String,
-    details: Option<String>,
-    source: u32,
-    severity: u32,
-}
-
-impl Issue {
-    pub fn from_tuple(
-        (description, details, source, severity): (String, String, u32, u32),
-    ) -> Self {
-        let details = if details.is_empty() {
-            None
-        } else {
-            Some(details)
-        };
-
-        Self {
-            description,
-            details,
-            source,
-            severity,
-        }
-    }
-}
-
 /// Builds a stream of the changes in the the `org.opensuse.Agama1.Issues`
 /// interface of the given D-Bus object.
 ///
@@ -357,7 +331,7 @@ pub async fn issues_stream(
     Ok(Box::pin(stream))
 }
 
-async fn build_issues_proxy<'a>(
+pub(crate) async fn build_issues_proxy<'a>(
     dbus: &zbus::Connection,
     destination: &str,
     path: &str,
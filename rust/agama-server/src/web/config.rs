@@ -14,11 +14,31 @@ use config::{Config, ConfigError, File};
 use rand::distributions::{Alphanumeric, DistString};
 use serde::Deserialize;
 
+/// Default lifetime of the authentication tokens (in seconds).
+const DEFAULT_TOKEN_LIFETIME: u64 = 24 * 60 * 60;
+/// Maximum lifetime of the authentication tokens (in seconds).
+const MAX_TOKEN_LIFETIME: u64 = 365 * 24 * 60 * 60;
+/// Default number of failed login attempts before locking the client out.
+const DEFAULT_LOGIN_MAX_ATTEMPTS: u32 = 5;
+/// Default waiting time after a failed login attempt (in seconds).
+const DEFAULT_LOGIN_BACKOFF: u64 = 1;
+/// Default lockout time (in seconds).
+const DEFAULT_LOGIN_LOCKOUT: u64 = 5 * 60;
+
 /// Web service configuration.
 #[derive(Clone, Debug, Deserialize)]
 pub struct ServiceConfig {
     /// Key to sign the JSON Web Tokens.
     pub jwt_secret: String,
+    /// Lifetime of the tokens issued by the server (in seconds).
+    pub token_lifetime: u64,
+    /// Number of consecutive failed login attempts before locking the client out.
+    pub login_max_attempts: u32,
+    /// Waiting time after a failed login attempt (in seconds). It doubles with each consecutive
+    /// failure.
+    pub login_backoff: u64,
+    /// How long a client is locked out after too many failed login attempts (in seconds).
+    pub login_lockout: u64,
 }
 
 impl ServiceConfig {
@@ -29,11 +49,28 @@ impl ServiceConfig {
 
         let config = Config::builder()
             .set_default("jwt_secret", jwt_secret)?
+            .set_default("token_lifetime", DEFAULT_TOKEN_LIFETIME)?
+            .set_default("login_max_attempts", DEFAULT_LOGIN_MAX_ATTEMPTS)?
+            .set_default("login_backoff", DEFAULT_LOGIN_BACKOFF)?
+            .set_default("login_lockout", DEFAULT_LOGIN_LOCKOUT)?
             .add_source(File::with_name("/usr/etc/agama.d/server").required(false))
             .add_source(File::with_name("/etc/agama.d/server").required(false))
             .add_source(File::with_name("etc/agama.d/server").required(false))
             .build()?;
-        config.try_deserialize()
+        let config: Self = config.try_deserialize()?;
+        Ok(config.clamped())
+    }
+
+    /// Returns the configuration with the values limited to the supported ranges.
+    fn clamped(mut self) -> Self {
+        if self.token_lifetime > MAX_TOKEN_LIFETIME {
+            tracing::warn!(
+                "The token lifetime is too long, using {} seconds instead",
+                MAX_TOKEN_LIFETIME
+            );
+            self.token_lifetime = MAX_TOKEN_LIFETIME;
+        }
+        self
     }
 }
 
@@ -41,6 +78,27 @@ impl Default for ServiceConfig {
     fn default() -> Self {
         Self {
             jwt_secret: "".to_string(),
+            token_lifetime: DEFAULT_TOKEN_LIFETIME,
+            login_max_attempts: DEFAULT_LOGIN_MAX_ATTEMPTS,
+            login_backoff: DEFAULT_LOGIN_BACKOFF,
+            login_lockout: DEFAULT_LOGIN_LOCKOUT,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceConfig, MAX_TOKEN_LIFETIME};
+
+    #[test]
+    fn test_clamp_token_lifetime() {
+        let config = ServiceConfig {
+            token_lifetime: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(config.clamped().token_lifetime, MAX_TOKEN_LIFETIME);
+
+        let config = ServiceConfig::default().clamped();
+        assert_eq!(config.token_lifetime, super::DEFAULT_TOKEN_LIFETIME);
+    }
+}
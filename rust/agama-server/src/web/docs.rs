@@ -4,6 +4,7 @@ use utoipa::OpenApi;
     info(description = "Agama web API description"),
     paths(
         crate::l10n::web::get_config,
+        crate::web::health::health,
         crate::l10n::web::keymaps,
         crate::l10n::web::locales,
         crate::l10n::web::set_config,
@@ -11,6 +12,7 @@ use utoipa::OpenApi;
         crate::manager::web::finish_action,
         crate::manager::web::install_action,
         crate::manager::web::installer_status,
+        crate::manager::web::installer_issues,
         crate::manager::web::probe_action,
         crate::network::web::add_connection,
         crate::network::web::apply,
@@ -25,6 +27,11 @@ use utoipa::OpenApi;
         crate::questions::web::delete_question,
         crate::questions::web::create_question,
         crate::questions::web::list_questions,
+        crate::questions::web::get_mode,
+        crate::questions::web::set_mode,
+        crate::questions::web::add_answers,
+        crate::settings::web::get_config,
+        crate::settings::web::set_config,
         crate::software::web::get_config,
         crate::software::web::patterns,
         crate::software::web::probe,
@@ -33,9 +40,11 @@ use utoipa::OpenApi;
         crate::software::web::set_config,
         crate::storage::web::actions,
         crate::storage::web::devices_dirty,
+        crate::storage::web::get_config,
         crate::storage::web::get_proposal_settings,
         crate::storage::web::probe,
         crate::storage::web::product_params,
+        crate::storage::web::set_config,
         crate::storage::web::set_proposal_settings,
         crate::storage::web::staging_devices,
         crate::storage::web::system_devices,
@@ -97,7 +106,14 @@ use utoipa::OpenApi;
         schemas(crate::l10n::LocaleEntry),
         schemas(crate::l10n::TimezoneEntry),
         schemas(agama_lib::localization::model::LocaleConfig),
-        schemas(crate::manager::web::InstallerStatus),
+        schemas(agama_lib::issue::IssueSeverity),
+        schemas(agama_lib::issue::IssueSource),
+        schemas(agama_lib::issue::IssueSummary),
+        schemas(crate::web::health::HealthResponse),
+        schemas(crate::web::health::ServiceHealth),
+        schemas(crate::web::health::ServiceStatus),
+        schemas(agama_lib::manager::InstallationBlockers),
+        schemas(agama_lib::manager::InstallerStatus),
         schemas(crate::network::model::Connection),
         schemas(crate::network::model::Device),
         schemas(agama_lib::questions::model::Answer),
@@ -105,6 +121,7 @@ use utoipa::OpenApi;
         schemas(agama_lib::questions::model::GenericQuestion),
         schemas(agama_lib::questions::model::PasswordAnswer),
         schemas(agama_lib::questions::model::Question),
+        schemas(agama_lib::questions::model::QuestionsMode),
         schemas(agama_lib::questions::model::QuestionWithPassword),
         schemas(agama_lib::software::model::SoftwareConfig),
         schemas(crate::software::web::SoftwareProposal),
@@ -1,10 +1,12 @@
 use crate::network::model::NetworkChange;
 use agama_lib::{
+    issue::Issue,
     jobs::Job,
     localization::model::LocaleConfig,
     manager::InstallationPhase,
     product::RegistrationRequirement,
     progress::Progress,
+    questions::model::Question,
     software::SelectedBy,
     storage::model::dasd::{DASDDevice, DASDFormatSummary},
     storage::ISCSINode,
@@ -14,8 +16,6 @@ use serde::Serialize;
 use std::collections::HashMap;
 use tokio::sync::broadcast::{Receiver, Sender};
 
-use super::common::Issue;
-
 #[derive(Clone, Debug, Serialize)]
 #[serde(tag = "type")]
 pub enum Event {
@@ -53,6 +53,17 @@ pub enum Event {
         patterns: HashMap<String, SelectedBy>,
     },
     QuestionsChanged,
+    QuestionAdded {
+        question: Question,
+    },
+    /// The answer of a question. It does not include the password, if any.
+    QuestionAnswered {
+        id: u32,
+        answer: String,
+    },
+    QuestionRemoved {
+        id: u32,
+    },
     InstallationPhaseChanged {
         phase: InstallationPhase,
     },
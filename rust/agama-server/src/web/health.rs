@@ -0,0 +1,259 @@
+//! Implements the health endpoint.
+//!
+//! It checks whether the backend services are reachable and, when they implement the
+//! `org.opensuse.Agama1.ServiceStatus` interface, whether they are busy. It answers with a 503
+//! status code when any of them is not reachable, so it can be used as a readiness probe.
+
+use agama_lib::{error::ServiceError, proxies::ServiceStatusProxy};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::time::Duration;
+use utoipa::ToSchema;
+use zbus::fdo::PeerProxy;
+
+/// Maximum time to wait for a service to answer.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How to check a service.
+#[derive(Clone, Copy)]
+enum Check {
+    /// Read the `org.opensuse.Agama1.ServiceStatus` interface.
+    ServiceStatus,
+    /// Ping the service (`org.freedesktop.DBus.Peer`).
+    Ping,
+    /// Ping the service on the system bus.
+    SystemPing,
+}
+
+/// Services to check: (name, D-Bus service, D-Bus path, check).
+const SERVICES: [(&str, &str, &str, Check); 7] = [
+    (
+        "manager",
+        "org.opensuse.Agama.Manager1",
+        "/org/opensuse/Agama/Manager1",
+        Check::ServiceStatus,
+    ),
+    (
+        "software",
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1",
+        Check::ServiceStatus,
+    ),
+    (
+        "storage",
+        "org.opensuse.Agama.Storage1",
+        "/org/opensuse/Agama/Storage1",
+        Check::ServiceStatus,
+    ),
+    (
+        "users",
+        "org.opensuse.Agama.Manager1",
+        "/org/opensuse/Agama/Users1",
+        Check::ServiceStatus,
+    ),
+    (
+        "questions",
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Questions",
+        Check::Ping,
+    ),
+    (
+        "locale",
+        "org.opensuse.Agama1",
+        "/org/opensuse/Agama1/Locale",
+        Check::Ping,
+    ),
+    (
+        "network",
+        "org.freedesktop.NetworkManager",
+        "/org/freedesktop/NetworkManager",
+        Check::SystemPing,
+    ),
+];
+
+/// Status of a service.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ServiceStatus {
+    Idle,
+    Busy,
+}
+
+/// Health of a backend service.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceHealth {
+    /// Service name (e.g., "storage").
+    name: String,
+    /// D-Bus service name.
+    bus_name: String,
+    /// Whether the service answered.
+    reachable: bool,
+    /// Service status, if the service reports it.
+    status: Option<ServiceStatus>,
+    /// Error, if the service is not reachable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Health of the backend services.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// Whether all the services are reachable.
+    ready: bool,
+    services: Vec<ServiceHealth>,
+}
+
+impl HealthResponse {
+    /// Builds the response from the health of each service.
+    ///
+    /// * `services`: health of the services.
+    fn new(services: Vec<ServiceHealth>) -> Self {
+        Self {
+            ready: services.iter().all(|s| s.reachable),
+            services,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    dbus: zbus::Connection,
+    system_dbus: zbus::Connection,
+}
+
+/// Sets up and returns the axum service for the health endpoint.
+///
+/// It connects to the system bus to check the services living there (e.g., NetworkManager).
+///
+/// * `dbus`: connection to Agama's D-Bus server.
+pub async fn health_service(dbus: zbus::Connection) -> Result<Router, ServiceError> {
+    let system_dbus = zbus::Connection::system().await?;
+    let state = HealthState { dbus, system_dbus };
+    Ok(Router::new().route("/", get(health)).with_state(state))
+}
+
+/// Returns the health of the backend services.
+#[utoipa::path(get, path = "/health", context_path = "/api", responses(
+    (status = 200, description = "All the services are reachable", body = HealthResponse),
+    (status = 503, description = "Some services are not reachable", body = HealthResponse)
+))]
+async fn health(State(state): State<HealthState>) -> impl IntoResponse {
+    let checks = SERVICES.iter().map(|(name, destination, path, check)| {
+        service_health(&state, name, destination, path, *check)
+    });
+    let response = HealthResponse::new(join_all(checks).await);
+    let code = if response.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(response))
+}
+
+/// Checks the health of a service.
+///
+/// * `state`: state holding the D-Bus connections.
+/// * `name`: service name.
+/// * `destination`: D-Bus service name.
+/// * `path`: D-Bus object path.
+/// * `check`: how to check the service.
+async fn service_health(
+    state: &HealthState,
+    name: &str,
+    destination: &str,
+    path: &str,
+    check: Check,
+) -> ServiceHealth {
+    let result = tokio::time::timeout(TIMEOUT, check_service(state, destination, path, check))
+        .await
+        .unwrap_or_else(|_| Err(ServiceError::InternalError("Timeout".to_string())));
+    let (status, error) = match result {
+        Ok(status) => (status, None),
+        Err(error) => (None, Some(error.to_string())),
+    };
+
+    ServiceHealth {
+        name: name.to_string(),
+        bus_name: destination.to_string(),
+        reachable: error.is_none(),
+        status,
+        error,
+    }
+}
+
+/// Checks whether a service answers, returning its status if it is available.
+///
+/// * `state`: state holding the D-Bus connections.
+/// * `destination`: D-Bus service name.
+/// * `path`: D-Bus object path.
+/// * `check`: how to check the service.
+async fn check_service(
+    state: &HealthState,
+    destination: &str,
+    path: &str,
+    check: Check,
+) -> Result<Option<ServiceStatus>, ServiceError> {
+    match check {
+        Check::ServiceStatus => {
+            let proxy = ServiceStatusProxy::builder(&state.dbus)
+                .destination(destination.to_string())?
+                .path(path.to_string())?
+                .cache_properties(zbus::CacheProperties::No)
+                .build()
+                .await?;
+            let status = match proxy.current().await? {
+                0 => ServiceStatus::Idle,
+                _ => ServiceStatus::Busy,
+            };
+            Ok(Some(status))
+        }
+        Check::Ping => {
+            ping(&state.dbus, destination, path).await?;
+            Ok(None)
+        }
+        Check::SystemPing => {
+            ping(&state.system_dbus, destination, path).await?;
+            Ok(None)
+        }
+    }
+}
+
+async fn ping(dbus: &zbus::Connection, destination: &str, path: &str) -> Result<(), ServiceError> {
+    let proxy = PeerProxy::builder(dbus)
+        .destination(destination.to_string())?
+        .path(path.to_string())?
+        .build()
+        .await?;
+    proxy.ping().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HealthResponse, ServiceHealth, ServiceStatus};
+
+    fn service(name: &str, reachable: bool) -> ServiceHealth {
+        ServiceHealth {
+            name: name.to_string(),
+            bus_name: format!("org.opensuse.Agama.{name}"),
+            reachable,
+            status: reachable.then_some(ServiceStatus::Idle),
+            error: (!reachable).then(|| "Not found".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_health_response() {
+        let response = HealthResponse::new(vec![service("Manager1", true)]);
+        assert!(response.ready);
+
+        let response =
+            HealthResponse::new(vec![service("Manager1", true), service("Storage1", false)]);
+        assert!(!response.ready);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["services"][0]["status"], "idle");
+        assert_eq!(json["services"][1]["error"], "Not found");
+    }
+}
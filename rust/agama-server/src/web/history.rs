@@ -0,0 +1,228 @@
+//! Implements a history of the events.
+//!
+//! Each event gets a monotonically increasing identifier (starting at 1), which is included in
+//! the JSON representation as the `id` field. The history keeps the latest events, so clients
+//! can reconnect and receive the events they missed. If those events are not available anymore,
+//! the client gets a [Replay::ResyncNeeded] and it should read the state again.
+
+use super::{Event, EventsSender};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
+
+/// Default number of events to keep in the history.
+pub const DEFAULT_HISTORY_SIZE: usize = 1024;
+
+/// Event as it is sent to the clients.
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    /// Event identifier.
+    pub id: u64,
+    /// JSON representation of the event, including the identifier.
+    pub json: serde_json::Value,
+}
+
+/// Events to send to a client when it connects.
+#[derive(Debug)]
+pub enum Replay {
+    /// Events the client missed.
+    Events(Vec<RecordedEvent>),
+    /// Some of the events the client missed are not available anymore.
+    ResyncNeeded,
+}
+
+impl Replay {
+    /// JSON message to tell the client that it must read the state again.
+    pub fn resync_message() -> serde_json::Value {
+        serde_json::json!({ "type": "ResyncNeeded" })
+    }
+}
+
+/// Keeps the latest events, assigning an identifier to each one.
+#[derive(Clone)]
+pub struct EventsHistory {
+    inner: Arc<Mutex<HistoryInner>>,
+}
+
+struct HistoryInner {
+    next_id: u64,
+    skipped: u64,
+    size: usize,
+    events: VecDeque<RecordedEvent>,
+    tx: Sender<RecordedEvent>,
+}
+
+impl EventsHistory {
+    /// Returns a new history which records the events sent through the given channel.
+    ///
+    /// It spawns a task to record the events, so it must run within a Tokio runtime.
+    ///
+    /// * `events`: channel to record the events from.
+    /// * `size`: maximum number of events to keep.
+    pub fn new(events: &EventsSender, size: usize) -> Self {
+        let size = size.max(1);
+        let (tx, _) = broadcast::channel(size);
+        let history = Self {
+            inner: Arc::new(Mutex::new(HistoryInner {
+                next_id: 1,
+                skipped: 0,
+                size,
+                events: VecDeque::with_capacity(size),
+                tx,
+            })),
+        };
+
+        let mut rx = events.subscribe();
+        let recorder = history.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => recorder.record(&event),
+                    Err(RecvError::Lagged(missed)) => recorder.skip(missed),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        history
+    }
+
+    /// Subscribes to the new events, returning the ones that happened after the given id.
+    ///
+    /// No events are replayed if `since` is `None`.
+    ///
+    /// * `since`: identifier of the last event received by the client.
+    pub fn subscribe(&self, since: Option<u64>) -> (Replay, Receiver<RecordedEvent>) {
+        let inner = self.inner.lock().unwrap();
+        let rx = inner.tx.subscribe();
+        let Some(since) = since else {
+            return (Replay::Events(vec![]), rx);
+        };
+
+        let first_id = inner.events.front().map_or(inner.next_id, |e| e.id);
+        // a greater id may come from a previous instance of the server
+        if since.saturating_add(1) < first_id || since >= inner.next_id {
+            return (Replay::ResyncNeeded, rx);
+        }
+
+        let missed = inner
+            .events
+            .iter()
+            .filter(|e| e.id > since)
+            .cloned()
+            .collect();
+        (Replay::Events(missed), rx)
+    }
+
+    /// Records an event.
+    ///
+    /// * `event`: event to record.
+    fn record(&self, event: &Event) {
+        let mut json = match serde_json::to_value(event) {
+            Ok(json) => json,
+            Err(error) => {
+                tracing::warn!("Could not serialize the event: {error}");
+                return;
+            }
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        if let Some(object) = json.as_object_mut() {
+            object.insert("id".to_string(), id.into());
+        }
+
+        let recorded = RecordedEvent { id, json };
+        if inner.events.len() == inner.size {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(recorded.clone());
+        _ = inner.tx.send(recorded);
+    }
+
+    /// Skips the events that could not be recorded.
+    ///
+    /// The previous events are discarded, so the clients which missed the skipped events must
+    /// resync.
+    ///
+    /// * `missed`: number of events that could not be recorded.
+    fn skip(&self, missed: u64) {
+        tracing::warn!("The events history missed {missed} events");
+        let mut inner = self.inner.lock().unwrap();
+        inner.next_id += missed;
+        inner.skipped += missed;
+        inner.events.clear();
+    }
+
+    /// Number of events that could not be recorded.
+    pub fn skipped(&self) -> u64 {
+        self.inner.lock().unwrap().skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventsHistory, Replay};
+    use crate::web::Event;
+    use std::time::Duration;
+    use tokio::sync::broadcast::channel;
+
+    async fn send_events(history: &EventsHistory, tx: &super::EventsSender, count: usize) {
+        let (_, mut rx) = history.subscribe(None);
+        for _ in 0..count {
+            tx.send(Event::QuestionsChanged).unwrap();
+            tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+
+    fn replayed_ids(replay: Replay) -> Vec<u64> {
+        match replay {
+            Replay::Events(events) => events.iter().map(|e| e.id).collect(),
+            Replay::ResyncNeeded => panic!("Unexpected resync"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_events() {
+        let (tx, _) = channel(16);
+        let history = EventsHistory::new(&tx, 3);
+        let (replay, _) = history.subscribe(Some(0));
+        assert_eq!(replayed_ids(replay), Vec::<u64>::new());
+
+        send_events(&history, &tx, 2).await;
+        let (replay, _) = history.subscribe(Some(0));
+        assert_eq!(replayed_ids(replay), vec![1, 2]);
+        let (replay, _) = history.subscribe(None);
+        assert_eq!(replayed_ids(replay), Vec::<u64>::new());
+
+        send_events(&history, &tx, 2).await;
+        let (replay, _) = history.subscribe(Some(2));
+        assert_eq!(replayed_ids(replay), vec![3, 4]);
+
+        let (replay, _) = history.subscribe(Some(1));
+        assert_eq!(replayed_ids(replay), vec![2, 3, 4]);
+        let (replay, _) = history.subscribe(Some(0));
+        assert!(matches!(replay, Replay::ResyncNeeded));
+        let (replay, _) = history.subscribe(Some(10));
+        assert!(matches!(replay, Replay::ResyncNeeded));
+    }
+
+    #[tokio::test]
+    async fn test_replay_from_unknown_id() {
+        let (tx, _) = channel(16);
+        let history = EventsHistory::new(&tx, 3);
+        let (replay, _) = history.subscribe(Some(u64::MAX));
+        assert!(matches!(replay, Replay::ResyncNeeded));
+
+        // the history is still usable
+        send_events(&history, &tx, 1).await;
+        let (replay, _) = history.subscribe(Some(0));
+        assert_eq!(replayed_ids(replay), vec![1]);
+    }
+}
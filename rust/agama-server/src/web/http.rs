@@ -1,17 +1,19 @@
 //! Implements the basic handlers for the HTTP-based API (login, logout, ping, etc.).
 
 use super::{auth::AuthError, state::ServiceState};
-use agama_lib::auth::{AuthToken, TokenClaims};
+use agama_lib::auth::{AuthToken, TokenClaims, TokenScope};
 use axum::{
     body::Body,
-    extract::{Query, State},
+    extract::{ConnectInfo, Query, State},
     http::{header, HeaderMap, HeaderValue, StatusCode},
     response::IntoResponse,
     Json,
 };
 use axum_extra::extract::cookie::CookieJar;
-use pam::Client;
+use chrono::Duration;
+use pam::{Client, PamError};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use utoipa::ToSchema;
 
 #[derive(Serialize, ToSchema)]
@@ -42,19 +44,124 @@ pub struct LoginRequest {
 }
 
 #[utoipa::path(post, path = "/api/auth", responses(
-    (status = 200, description = "The user has been successfully authenticated.", body = AuthResponse)
+    (status = 200, description = "The user has been successfully authenticated.", body = AuthResponse),
+    (status = 429, description = "Too many failed attempts. Check the Retry-After header.")
 ))]
 pub async fn login(
     State(state): State<ServiceState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
     Json(login): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
+    let client = connect_info.map(|ConnectInfo(addr)| client_ip(&addr));
+    let attempt = match state.login_throttle.check(client) {
+        Ok(attempt) => attempt,
+        Err(wait) => {
+            // round up, so the client does not retry too early
+            let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            return Err(AuthError::TooManyAttempts(seconds));
+        }
+    };
+
+    if let Err(error) = authenticate(login.password) {
+        attempt.failure();
+        return Err(error.into());
+    }
+    attempt.success();
+
+    issue_token(&state, None)
+}
+
+/// Returns the IP address of the client.
+///
+/// IPv4-mapped IPv6 addresses are converted to plain IPv4 ones, so the same client is always
+/// identified by the same address.
+///
+/// * `addr`: client's socket address.
+fn client_ip(addr: &SocketAddr) -> IpAddr {
+    match addr.ip() {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip => ip,
+    }
+}
+
+/// Authenticates the root user through PAM.
+///
+/// * `password`: root password.
+fn authenticate(password: String) -> Result<(), PamError> {
     let mut pam_client = Client::with_password("agama")?;
     pam_client
         .conversation_mut()
-        .set_credentials("root", login.password);
-    pam_client.authenticate()?;
+        .set_credentials("root", password);
+    pam_client.authenticate()
+}
+
+#[utoipa::path(post, path = "/api/auth/refresh", responses(
+    (status = 200, description = "A new token has been issued and the old one has been revoked.", body = AuthResponse),
+    (status = 400, description = "The user is not authenticated.")
+))]
+pub async fn refresh(
+    State(state): State<ServiceState>,
+    claims: TokenClaims,
+) -> Result<impl IntoResponse, AuthError> {
+    let response = issue_token(&state, claims.scope)?;
+    state.revoked_tokens.revoke(&claims);
+    Ok(response)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TokenRequest {
+    /// Scope of the new token.
+    scope: TokenScope,
+    /// Lifetime of the new token (in seconds). The one from the server configuration is used by
+    /// default.
+    lifetime: Option<u64>,
+}
+
+/// Issues a token restricted to the given scope.
+///
+/// Only a token without restrictions (e.g., obtained with the root password) can issue new
+/// tokens.
+#[utoipa::path(post, path = "/api/auth/tokens", request_body = TokenRequest, responses(
+    (status = 200, description = "The token has been issued.", body = AuthResponse),
+    (status = 400, description = "The user is not authenticated."),
+    (status = 403, description = "The user is not allowed to issue tokens.")
+))]
+pub async fn create_token(
+    State(state): State<ServiceState>,
+    claims: TokenClaims,
+    Json(request): Json<TokenRequest>,
+) -> Result<Json<AuthResponse>, AuthError> {
+    if claims.scope.is_some() {
+        return Err(AuthError::UnrestrictedTokenRequired);
+    }
+
+    // scoped tokens cannot live longer than the regular ones
+    let lifetime = request.lifetime.map_or(state.config.token_lifetime, |l| {
+        l.min(state.config.token_lifetime)
+    });
+    let claims = TokenClaims::new(Duration::seconds(lifetime as i64)).with_scope(request.scope);
+    let token = AuthToken::generate_with_claims(&state.config.jwt_secret, &claims)?;
+    Ok(Json(AuthResponse {
+        token: token.to_string(),
+    }))
+}
 
-    let token = AuthToken::generate(&state.config.jwt_secret)?;
+/// Issues a new token, according to the service configuration.
+///
+/// It returns the token in the response body and in the authentication cookie.
+///
+/// * `state`: service state.
+/// * `scope`: scope of the token (`None` means no restrictions).
+fn issue_token(
+    state: &ServiceState,
+    scope: Option<TokenScope>,
+) -> Result<impl IntoResponse, AuthError> {
+    let lifetime = Duration::seconds(state.config.token_lifetime as i64);
+    let mut claims = TokenClaims::new(lifetime);
+    if let Some(scope) = scope {
+        claims = claims.with_scope(scope);
+    }
+    let token = AuthToken::generate_with_claims(&state.config.jwt_secret, &claims)?;
     let content = Json(AuthResponse {
         token: token.to_string(),
     });
@@ -85,7 +192,10 @@ pub async fn login_from_query(
     let mut headers = HeaderMap::new();
 
     let token = AuthToken::new(&params.token);
-    if token.claims(&state.config.jwt_secret).is_ok() {
+    let valid = token
+        .claims(&state.config.jwt_secret)
+        .is_ok_and(|c| !state.revoked_tokens.contains(&c));
+    if valid {
         let cookie = auth_cookie_from_token(&token);
         headers.insert(
             header::SET_COOKIE,
@@ -98,9 +208,13 @@ pub async fn login_from_query(
 }
 
 #[utoipa::path(delete, path = "/api/auth", responses(
-    (status = 204, description = "The user has been logged out.")
+    (status = 204, description = "The user has been logged out and the token has been revoked.")
 ))]
-pub async fn logout(_claims: TokenClaims) -> Result<impl IntoResponse, AuthError> {
+pub async fn logout(
+    State(state): State<ServiceState>,
+    claims: TokenClaims,
+) -> Result<impl IntoResponse, AuthError> {
+    state.revoked_tokens.revoke(&claims);
     let mut headers = HeaderMap::new();
     let cookie = "agamaToken=deleted; HttpOnly; Expires=Thu, 01 Jan 1970 00:00:00 GMT".to_string();
     headers.insert(
@@ -0,0 +1,351 @@
+//! Implements the metrics endpoint.
+//!
+//! The metrics are exposed in the Prometheus text format. Most of them (installation phase,
+//! progress and issues) are updated from the events, so they are not available until the
+//! corresponding event is emitted.
+//!
+//! Reading the metrics requires an authorization token with, at least, the `read` scope.
+
+use super::{state::ServiceState, Event, EventsSender};
+use axum::{extract::State, http::header, response::IntoResponse};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Upper bounds (in seconds) of the requests latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Collects the web server metrics.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Mutex<MetricsInner>>,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    requests: BTreeMap<u16, u64>,
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    latency_count: u64,
+    clients: BTreeMap<&'static str, u64>,
+    lagged: u64,
+    phase: Option<u32>,
+    progress: BTreeMap<String, f64>,
+    issues: BTreeMap<(String, String), usize>,
+}
+
+impl Metrics {
+    /// Returns a new metrics collector which is updated with the events sent through the given
+    /// channel.
+    ///
+    /// It spawns a task to process the events, so it must run within a Tokio runtime.
+    ///
+    /// * `events`: channel to get the events from.
+    pub fn new(events: &EventsSender) -> Self {
+        let metrics = Self::default();
+        let mut rx = events.subscribe();
+        let recorder = metrics.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => recorder.record_event(&event),
+                    Err(RecvError::Lagged(missed)) => recorder.record_lagged(missed),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        metrics
+    }
+
+    /// Records an HTTP request.
+    ///
+    /// * `status`: response status code.
+    /// * `latency`: time to answer the request.
+    pub fn record_request(&self, status: u16, latency: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner.requests.entry(status).or_default() += 1;
+        let seconds = latency.as_secs_f64();
+        for (bucket, bound) in LATENCY_BUCKETS.iter().enumerate() {
+            if seconds <= *bound {
+                inner.latency_buckets[bucket] += 1;
+            }
+        }
+        inner.latency_sum += seconds;
+        inner.latency_count += 1;
+    }
+
+    /// Records that some events were dropped because a receiver could not keep up.
+    ///
+    /// * `missed`: number of dropped events.
+    pub fn record_lagged(&self, missed: u64) {
+        self.inner.lock().unwrap().lagged += missed;
+    }
+
+    /// Registers a new events client. It is unregistered when the returned guard is dropped.
+    ///
+    /// * `transport`: how the client gets the events (e.g., "websocket").
+    pub fn connect_client(&self, transport: &'static str) -> ClientGuard {
+        *self
+            .inner
+            .lock()
+            .unwrap()
+            .clients
+            .entry(transport)
+            .or_default() += 1;
+        ClientGuard {
+            metrics: self.clone(),
+            transport,
+        }
+    }
+
+    /// Updates the metrics according to an event.
+    ///
+    /// * `event`: emitted event.
+    fn record_event(&self, event: &Event) {
+        let mut inner = self.inner.lock().unwrap();
+        match event {
+            Event::InstallationPhaseChanged { phase } => inner.phase = Some(*phase as u32),
+            Event::Progress { service, progress } => {
+                let percentage = if progress.finished {
+                    100.0
+                } else if progress.max_steps == 0 {
+                    0.0
+                } else {
+                    f64::from(progress.current_step) * 100.0 / f64::from(progress.max_steps)
+                };
+                inner.progress.insert(service.clone(), percentage);
+            }
+            Event::IssuesChanged {
+                service,
+                path,
+                issues,
+            } => {
+                inner
+                    .issues
+                    .insert((service.clone(), path.clone()), issues.len());
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the metrics in the Prometheus text format.
+    ///
+    /// * `history_skipped`: events the history could not record.
+    pub fn render(&self, history_skipped: u64) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut text = String::new();
+
+        write_header(
+            &mut text,
+            "agama_http_requests_total",
+            "counter",
+            "Number of HTTP requests by status code.",
+        );
+        for (status, count) in &inner.requests {
+            _ = writeln!(
+                text,
+                "agama_http_requests_total{{status=\"{status}\"}} {count}"
+            );
+        }
+
+        write_header(
+            &mut text,
+            "agama_http_request_duration_seconds",
+            "histogram",
+            "Time to answer the HTTP requests.",
+        );
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(inner.latency_buckets) {
+            _ = writeln!(
+                text,
+                "agama_http_request_duration_seconds_bucket{{le=\"{bound}\"}} {count}"
+            );
+        }
+        _ = writeln!(
+            text,
+            "agama_http_request_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            inner.latency_count
+        );
+        _ = writeln!(
+            text,
+            "agama_http_request_duration_seconds_sum {}",
+            inner.latency_sum
+        );
+        _ = writeln!(
+            text,
+            "agama_http_request_duration_seconds_count {}",
+            inner.latency_count
+        );
+
+        write_header(
+            &mut text,
+            "agama_events_clients",
+            "gauge",
+            "Number of clients receiving the events.",
+        );
+        for transport in ["websocket", "sse"] {
+            let count = inner.clients.get(transport).unwrap_or(&0);
+            _ = writeln!(
+                text,
+                "agama_events_clients{{transport=\"{transport}\"}} {count}"
+            );
+        }
+
+        write_header(
+            &mut text,
+            "agama_events_lagged_total",
+            "counter",
+            "Number of events dropped because a receiver could not keep up.",
+        );
+        _ = writeln!(
+            text,
+            "agama_events_lagged_total {}",
+            inner.lagged + history_skipped
+        );
+
+        if let Some(phase) = inner.phase {
+            write_header(
+                &mut text,
+                "agama_installation_phase",
+                "gauge",
+                "Installation phase (0: startup, 1: config, 2: install).",
+            );
+            _ = writeln!(text, "agama_installation_phase {phase}");
+        }
+
+        write_header(
+            &mut text,
+            "agama_progress_percentage",
+            "gauge",
+            "Progress of the services.",
+        );
+        for (service, percentage) in &inner.progress {
+            _ = writeln!(
+                text,
+                "agama_progress_percentage{{service=\"{}\"}} {percentage}",
+                escape(service)
+            );
+        }
+
+        write_header(
+            &mut text,
+            "agama_issues",
+            "gauge",
+            "Number of issues by service and D-Bus object.",
+        );
+        for ((service, path), count) in &inner.issues {
+            _ = writeln!(
+                text,
+                "agama_issues{{service=\"{}\",path=\"{}\"}} {count}",
+                escape(service),
+                escape(path)
+            );
+        }
+
+        text
+    }
+}
+
+/// Keeps an events client registered (see [Metrics::connect_client]).
+pub struct ClientGuard {
+    metrics: Metrics,
+    transport: &'static str,
+}
+
+impl ClientGuard {
+    /// Metrics the client is registered in.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        let mut inner = self.metrics.inner.lock().unwrap();
+        if let Some(count) = inner.clients.get_mut(self.transport) {
+            *count = count.saturating_sub(1);
+        }
+    }
+}
+
+/// Writes the HELP and TYPE lines of a metric.
+fn write_header(text: &mut String, name: &str, metric_type: &str, help: &str) {
+    _ = writeln!(text, "# HELP {name} {help}");
+    _ = writeln!(text, "# TYPE {name} {metric_type}");
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub async fn metrics_handler(State(state): State<ServiceState>) -> impl IntoResponse {
+    let text = state.metrics.render(state.history.skipped());
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+    use crate::web::Event;
+    use agama_lib::{issue::Issue, manager::InstallationPhase, progress::Progress};
+    use std::time::Duration;
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = Metrics::default();
+        metrics.record_request(200, Duration::from_millis(20));
+        metrics.record_request(200, Duration::from_millis(300));
+        metrics.record_request(404, Duration::from_millis(1));
+        metrics.record_lagged(2);
+        let guard = metrics.connect_client("websocket");
+        metrics.record_event(&Event::InstallationPhaseChanged {
+            phase: InstallationPhase::Config,
+        });
+        metrics.record_event(&Event::Progress {
+            service: "org.opensuse.Agama.Manager1".to_string(),
+            progress: Progress {
+                current_step: 1,
+                max_steps: 4,
+                current_title: "Probing".to_string(),
+                finished: false,
+            },
+        });
+        metrics.record_event(&Event::IssuesChanged {
+            service: "org.opensuse.Agama.Storage1".to_string(),
+            path: "/org/opensuse/Agama/Storage1".to_string(),
+            issues: vec![Issue::from_tuple((
+                "No disk".to_string(),
+                "".to_string(),
+                1,
+                1,
+            ))],
+        });
+
+        let text = metrics.render(1);
+        assert!(text.contains("agama_http_requests_total{status=\"200\"} 2\n"));
+        assert!(text.contains("agama_http_requests_total{status=\"404\"} 1\n"));
+        assert!(text.contains("agama_http_request_duration_seconds_bucket{le=\"0.025\"} 2\n"));
+        assert!(text.contains("agama_http_request_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("agama_events_clients{transport=\"websocket\"} 1\n"));
+        assert!(text.contains("agama_events_lagged_total 3\n"));
+        assert!(text.contains("agama_installation_phase 1\n"));
+        assert!(text
+            .contains("agama_progress_percentage{service=\"org.opensuse.Agama.Manager1\"} 25\n"));
+        assert!(text.contains(
+            "agama_issues{service=\"org.opensuse.Agama.Storage1\",path=\"/org/opensuse/Agama/Storage1\"} 1\n"
+        ));
+
+        drop(guard);
+        let text = metrics.render(0);
+        assert!(text.contains("agama_events_clients{transport=\"websocket\"} 0\n"));
+    }
+}
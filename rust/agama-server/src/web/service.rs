@@ -1,6 +1,14 @@
-use super::http::{login, login_from_query, logout, session};
-use super::{config::ServiceConfig, state::ServiceState, EventsSender};
-use agama_lib::auth::TokenClaims;
+use super::auth::{require_scope, ScopeRules};
+use super::http::{create_token, login, login_from_query, logout, refresh, session};
+use super::{
+    config::ServiceConfig,
+    history::{EventsHistory, DEFAULT_HISTORY_SIZE},
+    metrics::Metrics,
+    state::ServiceState,
+    throttle::LoginThrottle,
+    EventsSender,
+};
+use agama_lib::auth::{TokenClaims, TokenScope};
 use axum::{
     body::Body,
     extract::Request,
@@ -24,14 +32,24 @@ use tracing::Span;
 ///
 /// * A static assets directory (`public_dir`).
 /// * A websocket at the `/ws` path.
-/// * An authentication endpoint at `/auth`.
+/// * A Server-Sent Events stream at the `/events` path, including the same events.
+/// * An authentication endpoint at `/auth` (and `/auth/refresh` to renew the token).
 /// * A 'ping' endpoint at '/ping'.
+/// * A metrics endpoint (in Prometheus format) at '/metrics'.
+/// * A number of public services (e.g., health checks) that are added using the
+///   `add_public_service` function.
 /// * A number of authenticated services that are added using the `add_service` function.
+///
+/// The scope of the token is checked for each authenticated request. See
+/// [MainServiceBuilder::require_scope] for further details.
 pub struct MainServiceBuilder {
     config: ServiceConfig,
     events: EventsSender,
+    history_size: usize,
     api_router: Router<ServiceState>,
+    public_router: Router<ServiceState>,
     public_dir: PathBuf,
+    scope_rules: ScopeRules,
 }
 
 impl MainServiceBuilder {
@@ -43,14 +61,19 @@ impl MainServiceBuilder {
     where
         P: AsRef<Path>,
     {
-        let api_router = Router::new().route("/ws", get(super::ws::ws_handler));
+        let api_router = Router::new()
+            .route("/ws", get(super::ws::ws_handler))
+            .route("/events", get(super::sse::sse_handler));
         let config = ServiceConfig::default();
 
         Self {
             events,
+            history_size: DEFAULT_HISTORY_SIZE,
             api_router,
+            public_router: Router::new(),
             config,
             public_dir: PathBuf::from(public_dir.as_ref()),
+            scope_rules: ScopeRules::default(),
         }
     }
 
@@ -58,6 +81,16 @@ impl MainServiceBuilder {
         Self { config, ..self }
     }
 
+    /// Sets the number of events to keep, so the WebSocket clients can get the ones they missed.
+    ///
+    /// * `size`: number of events.
+    pub fn with_history_size(self, size: usize) -> Self {
+        Self {
+            history_size: size,
+            ..self
+        }
+    }
+
     /// Add an authenticated service.
     ///
     /// * `path`: Path to mount the service under `/api`.
@@ -74,20 +107,63 @@ impl MainServiceBuilder {
         }
     }
 
+    /// Add a service which does not require authentication.
+    ///
+    /// * `path`: Path to mount the service under `/api`.
+    /// * `service`: Service to mount on the given `path`.
+    pub fn add_public_service<T>(self, path: &str, service: T) -> Self
+    where
+        T: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        T::Response: IntoResponse,
+        T::Future: Send + 'static,
+    {
+        Self {
+            public_router: self.public_router.nest_service(path, service),
+            ..self
+        }
+    }
+
+    /// Requires the given token scope to access the given path.
+    ///
+    /// By default, reading (`GET` and `HEAD`) requires the `read` scope and any other method
+    /// requires the `configure` scope.
+    ///
+    /// * `path`: path under `/api` (e.g., "/manager/install"). It includes any route under it.
+    /// * `scope`: required scope.
+    pub fn require_scope(mut self, path: &str, scope: TokenScope) -> Self {
+        self.scope_rules.add(path, scope);
+        self
+    }
+
     pub fn build(self) -> Router {
+        let login_throttle = LoginThrottle::new((&self.config).into());
+        let history = EventsHistory::new(&self.events, self.history_size);
+        let metrics = Metrics::new(&self.events);
         let state = ServiceState {
             config: self.config,
             events: self.events,
+            history,
+            metrics: metrics.clone(),
             public_dir: self.public_dir.clone(),
+            revoked_tokens: Default::default(),
+            login_throttle,
         };
 
         let api_router = self
             .api_router
+            .route("/metrics", get(super::metrics::metrics_handler))
             .route_layer(middleware::from_extractor_with_state::<TokenClaims, _>(
                 state.clone(),
             ))
+            .route_layer(middleware::from_fn_with_state(
+                self.scope_rules,
+                require_scope,
+            ))
             .route("/ping", get(super::http::ping))
-            .route("/auth", post(login).get(session).delete(logout));
+            .route("/auth", post(login).get(session).delete(logout))
+            .route("/auth/refresh", post(refresh))
+            .route("/auth/tokens", post(create_token))
+            .merge(self.public_router);
 
         tracing::info!("Serving static files from {}", self.public_dir.display());
         let serve = ServeDir::new(self.public_dir).precompressed_gzip();
@@ -103,7 +179,8 @@ impl MainServiceBuilder {
                         tracing::info!("request: {} {}", request.method(), request.uri().path())
                     })
                     .on_response(
-                        |response: &Response<Body>, latency: Duration, _span: &Span| {
+                        move |response: &Response<Body>, latency: Duration, _span: &Span| {
+                            metrics.record_request(response.status().as_u16(), latency);
                             tracing::info!("response: {} {:?}", response.status(), latency)
                         },
                     ),
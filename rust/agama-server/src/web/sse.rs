@@ -0,0 +1,84 @@
+//! Implements the Server-Sent Events (SSE) endpoint.
+//!
+//! It is an alternative to the WebSocket for clients that cannot use it (e.g., `curl`). The
+//! events are the same, using the type as the SSE event name and the JSON representation as the
+//! data. Clients can resume the stream using the `Last-Event-ID` header or the `since`
+//! parameter (e.g., `/events?since=42`), getting a `ResyncNeeded` event if the missed events are
+//! not available anymore.
+
+use super::{
+    history::{RecordedEvent, Replay},
+    state::ServiceState,
+};
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+#[derive(Deserialize)]
+pub struct EventsParams {
+    /// Identifier of the last event received by the client.
+    since: Option<u64>,
+}
+
+pub async fn sse_handler(
+    State(state): State<ServiceState>,
+    Query(params): Query<EventsParams>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let since = params.since.or_else(|| {
+        headers
+            .get(LAST_EVENT_ID)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    });
+
+    let (replay, rx) = state.history.subscribe(since);
+    let missed: Vec<SseEvent> = match replay {
+        Replay::Events(events) => events.iter().map(to_sse_event).collect(),
+        Replay::ResyncNeeded => vec![resync_event()],
+    };
+    // the client is unregistered when the stream is dropped
+    let client = state.metrics.connect_client("sse");
+    let events = BroadcastStream::new(rx).map(move |event| match event {
+        Ok(event) => to_sse_event(&event),
+        // the client cannot keep up with the events
+        Err(BroadcastStreamRecvError::Lagged(missed)) => {
+            client.metrics().record_lagged(missed);
+            resync_event()
+        }
+    });
+
+    let stream = tokio_stream::iter(missed).chain(events).map(Ok);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Converts an event to SSE.
+///
+/// * `event`: event to convert.
+fn to_sse_event(event: &RecordedEvent) -> SseEvent {
+    let event_type = event.json.get("type").and_then(|t| t.as_str());
+    let sse_event = match event_type {
+        Some(event_type) => SseEvent::default().event(event_type),
+        None => SseEvent::default(),
+    };
+    sse_event
+        .id(event.id.to_string())
+        .data(event.json.to_string())
+}
+
+/// Returns the SSE event to tell the client that it must read the state again.
+fn resync_event() -> SseEvent {
+    SseEvent::default()
+        .event("ResyncNeeded")
+        .data(Replay::resync_message().to_string())
+}
@@ -1,14 +1,22 @@
 //! Implements the web service state.
 
-use super::{config::ServiceConfig, EventsSender};
+use super::{
+    auth::RevokedTokens, config::ServiceConfig, history::EventsHistory, metrics::Metrics,
+    throttle::LoginThrottle, EventsSender,
+};
 use std::path::PathBuf;
 
 /// Web service state.
 ///
-/// It holds the service configuration, the current D-Bus connection and a channel to send events.
+/// It holds the service configuration, the current D-Bus connection, a channel to send events, the
+/// history of the events, the metrics, the list of revoked tokens and the failed login attempts.
 #[derive(Clone)]
 pub struct ServiceState {
     pub config: ServiceConfig,
     pub events: EventsSender,
+    pub history: EventsHistory,
+    pub metrics: Metrics,
     pub public_dir: PathBuf,
+    pub revoked_tokens: RevokedTokens,
+    pub login_throttle: LoginThrottle,
 }
@@ -0,0 +1,298 @@
+//! Implements the protection against brute-force attacks on the login endpoint.
+//!
+//! The failed login attempts are tracked per client (IP address). After each failure, the client
+//! must wait before trying again, and the waiting time doubles with each consecutive failure. When
+//! the client reaches the maximum number of attempts, it is locked out for a while.
+//!
+//! A client can have only one login attempt in progress, so it cannot bypass the backoff by
+//! trying several passwords in parallel.
+
+use super::config::ServiceConfig;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Failed login attempts of a client.
+#[derive(Debug)]
+struct Attempts {
+    /// Number of consecutive failures.
+    failures: u32,
+    /// Time of the last failure.
+    last_failure: Instant,
+    /// The client cannot try again until this time.
+    blocked_until: Instant,
+    /// Whether there is a login attempt in progress.
+    in_flight: bool,
+}
+
+/// Limits for the login attempts.
+#[derive(Clone, Copy, Debug)]
+pub struct LoginLimits {
+    /// Number of consecutive failures before locking the client out.
+    pub max_attempts: u32,
+    /// Initial waiting time after a failure. It doubles with each consecutive failure.
+    pub backoff: Duration,
+    /// How long the client is locked out.
+    pub lockout: Duration,
+}
+
+impl From<&ServiceConfig> for LoginLimits {
+    fn from(config: &ServiceConfig) -> Self {
+        Self {
+            max_attempts: config.login_max_attempts,
+            backoff: Duration::from_secs(config.login_backoff),
+            lockout: Duration::from_secs(config.login_lockout),
+        }
+    }
+}
+
+/// Keeps track of the failed login attempts.
+///
+/// The clients are identified by their IP address. When the address is unknown, all those clients
+/// share the same record.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    limits: LoginLimits,
+    attempts: Arc<Mutex<HashMap<Option<IpAddr>, Attempts>>>,
+}
+
+impl LoginThrottle {
+    /// Creates a new throttle with the given limits.
+    ///
+    /// * `limits`: limits for the login attempts.
+    pub fn new(limits: LoginLimits) -> Self {
+        Self {
+            limits,
+            attempts: Default::default(),
+        }
+    }
+
+    /// Checks whether the client is allowed to try to log in and, if so, reserves the attempt.
+    ///
+    /// If it is not allowed (because it must wait or because it has another attempt in
+    /// progress), it returns how long the client must wait.
+    ///
+    /// * `client`: client's IP address.
+    pub fn check(&self, client: Option<IpAddr>) -> Result<LoginAttempt, Duration> {
+        self.check_at(client, Instant::now())?;
+        Ok(LoginAttempt {
+            throttle: self.clone(),
+            client,
+        })
+    }
+
+    /// Registers a successful login, forgetting the previous failures.
+    ///
+    /// * `client`: client's IP address.
+    fn success(&self, client: Option<IpAddr>) {
+        let mut attempts = self.attempts.lock().unwrap();
+        attempts.remove(&client);
+    }
+
+    /// Releases the attempt in progress, if any.
+    ///
+    /// * `client`: client's IP address.
+    fn release(&self, client: Option<IpAddr>) {
+        let mut attempts = self.attempts.lock().unwrap();
+        if let Some(record) = attempts.get_mut(&client) {
+            record.in_flight = false;
+        }
+    }
+
+    fn check_at(&self, client: Option<IpAddr>, now: Instant) -> Result<(), Duration> {
+        let mut attempts = self.attempts.lock().unwrap();
+        let record = attempts.entry(client).or_insert(Attempts {
+            failures: 0,
+            last_failure: now,
+            blocked_until: now,
+            in_flight: false,
+        });
+        if record.blocked_until > now {
+            return Err(record.blocked_until - now);
+        }
+        if record.in_flight {
+            return Err(self.limits.backoff.max(Duration::from_secs(1)));
+        }
+        record.in_flight = true;
+        Ok(())
+    }
+
+    fn failure_at(&self, client: Option<IpAddr>, now: Instant) {
+        let mut attempts = self.attempts.lock().unwrap();
+        // forget about the clients that did not fail for a while, unless they are blocked or
+        // trying to log in
+        attempts.retain(|_, r| {
+            r.in_flight
+                || r.blocked_until > now
+                || now.duration_since(r.last_failure) < self.limits.lockout
+        });
+
+        let record = attempts.entry(client).or_insert(Attempts {
+            failures: 0,
+            last_failure: now,
+            blocked_until: now,
+            in_flight: false,
+        });
+        record.in_flight = false;
+        record.failures += 1;
+        record.last_failure = now;
+
+        if record.failures >= self.limits.max_attempts {
+            tracing::warn!("Too many failed login attempts from {:?}", client);
+            record.failures = 0;
+            record.blocked_until = now + self.limits.lockout;
+        } else {
+            let factor = 2_u32.saturating_pow(record.failures - 1);
+            let backoff = self.limits.backoff.saturating_mul(factor);
+            record.blocked_until = now + backoff.min(self.limits.lockout);
+        }
+    }
+}
+
+/// Login attempt in progress.
+///
+/// Its result must be registered with [LoginAttempt::failure] or [LoginAttempt::success]. If
+/// it is dropped before, the attempt is just released.
+pub struct LoginAttempt {
+    throttle: LoginThrottle,
+    client: Option<IpAddr>,
+}
+
+impl LoginAttempt {
+    /// Registers that the attempt failed.
+    pub fn failure(self) {
+        self.throttle.failure_at(self.client, Instant::now())
+    }
+
+    /// Registers that the attempt succeeded, forgetting the previous failures.
+    pub fn success(self) {
+        self.throttle.success(self.client)
+    }
+}
+
+impl Drop for LoginAttempt {
+    fn drop(&mut self) {
+        self.throttle.release(self.client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoginLimits, LoginThrottle};
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        sync::{Arc, Barrier},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    const CLIENT: Option<IpAddr> = Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)));
+    const OTHER_CLIENT: Option<IpAddr> = Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 11)));
+
+    fn throttle() -> LoginThrottle {
+        LoginThrottle::new(LoginLimits {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+            lockout: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let throttle = throttle();
+        let now = Instant::now();
+        assert!(throttle.check_at(CLIENT, now).is_ok());
+
+        throttle.failure_at(CLIENT, now);
+        assert_eq!(throttle.check_at(CLIENT, now), Err(Duration::from_secs(1)));
+        assert!(throttle.check_at(OTHER_CLIENT, now).is_ok());
+
+        let now = now + Duration::from_secs(1);
+        assert!(throttle.check_at(CLIENT, now).is_ok());
+        throttle.failure_at(CLIENT, now);
+        assert_eq!(throttle.check_at(CLIENT, now), Err(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_lockout() {
+        let throttle = throttle();
+        let mut now = Instant::now();
+        for _ in 0..3 {
+            now += Duration::from_secs(10);
+            throttle.failure_at(CLIENT, now);
+        }
+        assert_eq!(throttle.check_at(CLIENT, now), Err(Duration::from_secs(60)));
+        assert!(throttle
+            .check_at(CLIENT, now + Duration::from_secs(60))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_success() {
+        let throttle = throttle();
+        let now = Instant::now();
+        throttle.failure_at(CLIENT, now);
+        throttle.success(CLIENT);
+        assert!(throttle.check_at(CLIENT, now).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_attempts() {
+        let throttle = throttle();
+        let barrier = Arc::new(Barrier::new(10));
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let throttle = throttle.clone();
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    // keep the attempt in progress until all the threads tried
+                    let attempt = throttle.check(CLIENT);
+                    barrier.wait();
+                    attempt.map(|a| a.failure()).is_ok()
+                })
+            })
+            .collect();
+        let allowed = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|ok| *ok)
+            .count();
+        assert_eq!(allowed, 1);
+
+        // the failure was registered, so the client must wait
+        assert!(throttle.check(CLIENT).is_err());
+        assert!(throttle.check(OTHER_CLIENT).is_ok());
+    }
+
+    #[test]
+    fn test_keep_attempts_in_progress() {
+        let throttle = throttle();
+        let now = Instant::now();
+        throttle.failure_at(OTHER_CLIENT, now);
+        let now = now + Duration::from_secs(61);
+        assert!(throttle.check_at(OTHER_CLIENT, now).is_ok());
+
+        // a failure of another client does not forget the attempt in progress
+        let later = now + Duration::from_secs(61);
+        throttle.failure_at(CLIENT, later);
+        assert!(throttle.check_at(OTHER_CLIENT, later).is_err());
+        throttle.failure_at(OTHER_CLIENT, later);
+        assert_eq!(
+            throttle.check_at(OTHER_CLIENT, later),
+            Err(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn test_release_attempt() {
+        let throttle = throttle();
+        let attempt = throttle.check(CLIENT).unwrap();
+        assert!(throttle.check(CLIENT).is_err());
+        drop(attempt);
+        assert!(throttle.check(CLIENT).is_ok());
+    }
+}
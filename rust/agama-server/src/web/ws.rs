@@ -1,26 +1,101 @@
 //! Implements the websocket handling.
+//!
+//! By default, all the events are sent to the client. The client can send an
+//! [EventsRequest::Subscribe] message to receive only the events it is interested in.
+//!
+//! Each event includes an `id`. When reconnecting, the client can use the `since` parameter
+//! (e.g., `/ws?since=42`) to get the events it missed. If they are not available anymore, or the
+//! client cannot keep up with the events, it gets a `{"type": "ResyncNeeded"}` message.
 
-use super::{state::ServiceState, EventsSender};
+use super::{
+    history::{RecordedEvent, Replay},
+    metrics::Metrics,
+    state::ServiceState,
+};
+use agama_lib::events::{EventsFilter, EventsRequest};
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
     },
     response::IntoResponse,
 };
+use serde::Deserialize;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+#[derive(Deserialize)]
+pub struct WebSocketParams {
+    /// Identifier of the last event received by the client.
+    since: Option<u64>,
+}
 
 pub async fn ws_handler(
     State(state): State<ServiceState>,
+    Query(params): Query<WebSocketParams>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state.events))
+    let (replay, rx) = state.history.subscribe(params.since);
+    ws.on_upgrade(move |socket| handle_socket(socket, replay, rx, state.metrics))
 }
 
-async fn handle_socket(mut socket: WebSocket, events: EventsSender) {
-    let mut rx = events.subscribe();
-    while let Ok(msg) = rx.recv().await {
-        if let Ok(json) = serde_json::to_string(&msg) {
-            _ = socket.send(Message::Text(json)).await;
+async fn handle_socket(
+    mut socket: WebSocket,
+    replay: Replay,
+    mut rx: Receiver<RecordedEvent>,
+    metrics: Metrics,
+) {
+    let _client = metrics.connect_client("websocket");
+    let mut filter = EventsFilter::default();
+
+    match replay {
+        Replay::Events(events) => {
+            for event in events {
+                _ = socket.send(Message::Text(event.json.to_string())).await;
+            }
+        }
+        Replay::ResyncNeeded => {
+            _ = socket
+                .send(Message::Text(Replay::resync_message().to_string()))
+                .await;
         }
     }
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                match event {
+                    Ok(event) => {
+                        if filter.matches(&event.json) {
+                            _ = socket.send(Message::Text(event.json.to_string())).await;
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        metrics.record_lagged(missed);
+                        let message = Replay::resync_message().to_string();
+                        _ = socket.send(Message::Text(message)).await;
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Text(text))) => handle_request(&text, &mut filter),
+                    Some(Ok(_)) => {}
+                    // the client closed the connection
+                    _ => break,
+                }
+            }
+        }
+    }
+}
+
+/// Handles a request from the client.
+///
+/// * `text`: request in JSON format.
+/// * `filter`: filter to apply to the events.
+fn handle_request(text: &str, filter: &mut EventsFilter) {
+    match serde_json::from_str(text) {
+        Ok(EventsRequest::Subscribe(new_filter)) => *filter = new_filter,
+        Err(error) => tracing::warn!("Invalid WebSocket request: {error}"),
+    }
 }
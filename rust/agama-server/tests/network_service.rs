@@ -1,7 +1,9 @@
 pub mod common;
 
 use agama_lib::error::ServiceError;
-use agama_lib::network::settings::{BondSettings, NetworkConnection};
+use agama_lib::network::settings::{
+    BondSettings, BridgeSettings, MatchSettings, NetworkConnection,
+};
 use agama_lib::network::types::{DeviceType, SSID};
 use agama_server::network::web::network_service;
 use agama_server::network::{
@@ -178,7 +180,7 @@ async fn test_add_bond_connection() -> Result<(), Box<dyn Error>> {
         interface: Some("bond0".to_string()),
         bond: Some(BondSettings {
             mode: "active-backup".to_string(),
-            ports: vec!["eth0".to_string()],
+            ports: Some(vec!["eth0".to_string()]),
             options: Some("primary=eth0".to_string()),
         }),
         ..Default::default()
@@ -217,6 +219,258 @@ async fn test_add_bond_connection() -> Result<(), Box<dyn Error>> {
     assert!(body.contains(r#""id":"bond0""#));
     assert!(body.contains(r#""mode":"active-backup""#));
     assert!(body.contains(r#""primary=eth0""#));
+    assert!(body.contains(r#""ports":["eth0"]"#));
+
+    Ok(())
+}
+
+#[test]
+async fn test_update_bond_without_ports() -> Result<(), Box<dyn Error>> {
+    let state = build_state().await;
+    let network_service = build_service(state.clone()).await?;
+
+    let bond0 = serde_json::json!({
+        "id": "bond0",
+        "interface": "bond0",
+        "bond": { "mode": "active-backup", "ports": ["eth0"] }
+    });
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(bond0.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let bond0 = serde_json::json!({
+        "id": "bond0",
+        "interface": "bond0",
+        "bond": { "mode": "balance-rr" }
+    });
+    let request = Request::builder()
+        .uri("/connections/bond0")
+        .header("Content-Type", "application/json")
+        .method(Method::PUT)
+        .body(bond0.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::builder()
+        .uri("/connections/bond0")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+    let response = network_service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""mode":"balance-rr""#));
+    assert!(body.contains(r#""ports":["eth0"]"#));
+
+    Ok(())
+}
+
+#[test]
+async fn test_add_bridge_with_unknown_port() -> Result<(), Box<dyn Error>> {
+    let state = build_state().await;
+    let network_service = build_service(state.clone()).await?;
+
+    let br0 = NetworkConnection {
+        id: "br0".to_string(),
+        interface: Some("br0".to_string()),
+        bridge: Some(BridgeSettings {
+            ports: Some(vec!["eth0".to_string(), "eth5".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(serde_json::to_string(&br0)?)
+        .unwrap();
+
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("Unknown port 'eth5'"));
+
+    let request = Request::builder()
+        .uri("/connections/br0")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+    let response = network_service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("Unknown connection id: br0"));
+
+    Ok(())
+}
+
+#[test]
+async fn test_add_connection_with_routes() -> Result<(), Box<dyn Error>> {
+    let state = build_state().await;
+    let network_service = build_service(state.clone()).await?;
+
+    let eth1 = serde_json::json!({
+        "id": "eth1",
+        "method4": "manual",
+        "addresses": ["192.168.1.10/24"],
+        "routes4": [
+            { "destination": "10.0.0.0/8", "nextHop": "192.168.1.1", "metric": 100, "table": 200 }
+        ],
+        "routes6": [{ "destination": "2001:db8::/64" }],
+        "routingRules4": [{ "priority": 1000, "from": "192.168.1.0/24", "table": 200 }]
+    });
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(eth1.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/connections/eth1")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(
+        r#""routes4":[{"destination":"10.0.0.0/8","nextHop":"192.168.1.1","metric":100,"table":200}]"#
+    ));
+    assert!(body.contains(r#""routes6":[{"destination":"2001:db8::/64"}]"#));
+    assert!(
+        body.contains(r#""routingRules4":[{"priority":1000,"from":"192.168.1.0/24","table":200}]"#)
+    );
+
+    let eth2 = serde_json::json!({
+        "id": "eth2",
+        "routes4": [{ "destination": "2001:db8::/64" }]
+    });
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(eth2.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("Unexpected IP family for '2001:db8::/64'"));
+
+    Ok(())
+}
+
+#[test]
+async fn test_update_connection_routes() -> Result<(), Box<dyn Error>> {
+    let state = build_state().await;
+    let network_service = build_service(state.clone()).await?;
+
+    let eth1 = serde_json::json!({
+        "id": "eth1",
+        "routes4": [{ "destination": "10.0.0.0/8" }],
+        "routes6": [{ "destination": "2001:db8::/64" }]
+    });
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(eth1.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // routes4 are kept, while routes6 are removed
+    let eth1 = serde_json::json!({ "id": "eth1", "routes6": [] });
+    let request = Request::builder()
+        .uri("/connections/eth1")
+        .header("Content-Type", "application/json")
+        .method(Method::PUT)
+        .body(eth1.to_string())
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::builder()
+        .uri("/connections/eth1")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+    let response = network_service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""routes4":[{"destination":"10.0.0.0/8"}]"#));
+    assert!(body.contains(r#""routes6":[]"#));
+
+    Ok(())
+}
+
+#[test]
+async fn test_add_connection_with_parent() -> Result<(), Box<dyn Error>> {
+    let state = build_state().await;
+    let network_service = build_service(state.clone()).await?;
+
+    let br0 = NetworkConnection {
+        id: "Bridge".to_string(),
+        interface: Some("br0".to_string()),
+        bridge: Some(BridgeSettings::default()),
+        ..Default::default()
+    };
+    let eth1 = NetworkConnection {
+        id: "eth1".to_string(),
+        parent: Some("br0".to_string()),
+        match_settings: Some(MatchSettings {
+            driver: vec!["ixgbe".to_string()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    for conn in [&br0, &eth1] {
+        let request = Request::builder()
+            .uri("/connections")
+            .header("Content-Type", "application/json")
+            .method(Method::POST)
+            .body(serde_json::to_string(conn)?)
+            .unwrap();
+        let response = network_service.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = Request::builder()
+        .uri("/connections/eth1")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    let conn: NetworkConnection = serde_json::from_str(&body)?;
+    assert_eq!(conn.parent, Some("Bridge".to_string()));
+    assert_eq!(conn.match_settings, eth1.match_settings);
+
+    let eth2 = NetworkConnection {
+        id: "eth2".to_string(),
+        parent: Some("eth0".to_string()),
+        ..Default::default()
+    };
+    let request = Request::builder()
+        .uri("/connections")
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(serde_json::to_string(&eth2)?)
+        .unwrap();
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     Ok(())
 }
@@ -1,15 +1,19 @@
 pub mod common;
 
-use agama_lib::auth::AuthToken;
-use agama_server::web::{MainServiceBuilder, ServiceConfig};
+use agama_lib::auth::{AuthToken, TokenClaims, TokenScope};
+use agama_lib::base_http_client::BaseHTTPClient;
+use agama_lib::events::{Event as ClientEvent, EventsClient, EventsFilter};
+use agama_server::web::{Event, EventsSender, MainServiceBuilder, ServiceConfig};
 use axum::{
     body::Body,
     http::{Method, Request, StatusCode},
     response::Response,
     routing::get,
+    Extension,
 };
 use common::body_to_string;
-use std::{error::Error, path::PathBuf};
+use http_body_util::BodyExt;
+use std::{error::Error, path::PathBuf, time::Duration};
 use tokio::{sync::broadcast::channel, test};
 use tower::ServiceExt;
 
@@ -39,6 +43,52 @@ async fn test_ping() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_public_service() -> Result<(), Box<dyn Error>> {
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_public_service("/public", get(protected))
+        .build();
+
+    let request = Request::builder()
+        .uri("/api/public")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    Ok(())
+}
+
+#[test]
+async fn test_metrics() -> Result<(), Box<dyn Error>> {
+    let web_service = scoped_service();
+
+    let request = Request::builder()
+        .uri("/api/ping")
+        .body(Body::empty())
+        .unwrap();
+    web_service.clone().oneshot(request).await.unwrap();
+
+    let request = Request::builder()
+        .uri("/api/metrics")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let token = scoped_token(TokenScope::Read);
+    let request = authenticated_request(Method::GET, "/api/metrics", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("agama_http_requests_total{status=\"200\"} 1\n"));
+    assert!(body.contains("agama_http_requests_total{status=\"400\"} 1\n"));
+    assert!(body.contains("agama_events_clients{transport=\"websocket\"} 0\n"));
+    Ok(())
+}
+
 async fn protected() -> String {
     "OK".to_string()
 }
@@ -46,6 +96,7 @@ async fn protected() -> String {
 async fn access_protected_route(token: &str, jwt_secret: &str) -> Response {
     let config = ServiceConfig {
         jwt_secret: jwt_secret.to_string(),
+        ..Default::default()
     };
     let (tx, _) = channel(16);
     let web_service = MainServiceBuilder::new(tx, public_dir())
@@ -83,3 +134,317 @@ async fn test_access_protected_route_failed() -> Result<(), Box<dyn Error>> {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     Ok(())
 }
+
+fn service_with_secret(jwt_secret: &str) -> axum::Router {
+    let config = ServiceConfig {
+        jwt_secret: jwt_secret.to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", get(protected))
+        .with_config(config)
+        .build()
+}
+
+fn authenticated_request(method: Method, uri: &str, token: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(method)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[test]
+async fn test_caller_token_is_available() -> Result<(), Box<dyn Error>> {
+    let token = scoped_token(TokenScope::Read);
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service(
+            "/whoami",
+            get(|Extension(token): Extension<AuthToken>| async move { token.to_string() }),
+        )
+        .with_config(ServiceConfig {
+            jwt_secret: "nots3cr3t".to_string(),
+            ..Default::default()
+        })
+        .build();
+
+    let request = authenticated_request(Method::GET, "/api/whoami", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, token.as_str());
+    Ok(())
+}
+
+#[test]
+async fn test_logout_revokes_token() -> Result<(), Box<dyn Error>> {
+    let token = AuthToken::generate("nots3cr3t")?;
+    let web_service = service_with_secret("nots3cr3t");
+
+    let request = authenticated_request(Method::DELETE, "/api/auth", token.as_str());
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = authenticated_request(Method::GET, "/api/protected", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_refresh_token() -> Result<(), Box<dyn Error>> {
+    let token = AuthToken::generate("nots3cr3t")?;
+    let web_service = service_with_secret("nots3cr3t");
+
+    let request = authenticated_request(Method::POST, "/api/auth/refresh", token.as_str());
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = body_to_string(response.into_body()).await;
+    let body: serde_json::Value = serde_json::from_str(&body)?;
+    let new_token = body["token"].as_str().unwrap();
+    assert_ne!(new_token, token.as_str());
+
+    let request = authenticated_request(Method::GET, "/api/protected", new_token);
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the old token is not valid anymore
+    let request = authenticated_request(Method::GET, "/api/protected", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+fn scoped_token(scope: TokenScope) -> AuthToken {
+    let claims = TokenClaims::default().with_scope(scope);
+    AuthToken::generate_with_claims("nots3cr3t", &claims).unwrap()
+}
+
+fn scoped_service() -> axum::Router {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", get(protected).post(protected))
+        .require_scope("/protected/install", TokenScope::Install)
+        .with_config(config)
+        .build()
+}
+
+#[test]
+async fn test_read_scope() -> Result<(), Box<dyn Error>> {
+    let token = scoped_token(TokenScope::Read);
+    let web_service = scoped_service();
+
+    let request = authenticated_request(Method::GET, "/api/protected", token.as_str());
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = authenticated_request(Method::POST, "/api/protected", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    Ok(())
+}
+
+#[test]
+async fn test_configure_scope() -> Result<(), Box<dyn Error>> {
+    let token = scoped_token(TokenScope::Configure);
+    let web_service = scoped_service();
+
+    let request = authenticated_request(Method::POST, "/api/protected", token.as_str());
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = authenticated_request(Method::POST, "/api/protected/install", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let token = scoped_token(TokenScope::Install);
+    let web_service = scoped_service();
+    let request = authenticated_request(Method::POST, "/api/protected/install", token.as_str());
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    Ok(())
+}
+
+async fn request_scoped_token(web_service: axum::Router, token: &str) -> Response {
+    let request = Request::builder()
+        .uri("/api/auth/tokens")
+        .method(Method::POST)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(r#"{"scope": "read"}"#))
+        .unwrap();
+    web_service.oneshot(request).await.unwrap()
+}
+
+#[test]
+async fn test_create_scoped_token() -> Result<(), Box<dyn Error>> {
+    let token = AuthToken::generate("nots3cr3t")?;
+    let response = request_scoped_token(scoped_service(), token.as_str()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = body_to_string(response.into_body()).await;
+    let body: serde_json::Value = serde_json::from_str(&body)?;
+    let new_token = AuthToken::new(body["token"].as_str().unwrap());
+    let claims = new_token.claims("nots3cr3t")?;
+    assert_eq!(claims.scope, Some(TokenScope::Read));
+
+    // scoped tokens cannot issue new tokens
+    let response = request_scoped_token(scoped_service(), new_token.as_str()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    Ok(())
+}
+
+async fn login(web_service: axum::Router, password: &str) -> Response {
+    let request = Request::builder()
+        .uri("/api/auth")
+        .method(Method::POST)
+        .header("Content-Type", "application/json")
+        .body(Body::from(format!(r#"{{"password": "{}"}}"#, password)))
+        .unwrap();
+    web_service.oneshot(request).await.unwrap()
+}
+
+#[test]
+async fn test_login_throttling() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        login_max_attempts: 2,
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .with_config(config)
+        .build();
+
+    let response = login(web_service.clone(), "wrong").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = login(web_service.clone(), "wrong").await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()["Retry-After"], "1");
+    Ok(())
+}
+
+/// Serves the web service through a local TCP port, returning an authenticated client to reach
+/// it and the channel to emit events.
+async fn serve_events() -> Result<(BaseHTTPClient, EventsSender), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx.clone(), public_dir())
+        .with_config(config)
+        .with_history_size(3)
+        .build();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, web_service).await });
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let client = BaseHTTPClient::default()
+        .with_base_url(&format!("http://{address}/api"))
+        .with_token(&token)?;
+    Ok((client, tx))
+}
+
+#[test]
+async fn test_events_subscription() -> Result<(), Box<dyn Error>> {
+    let (client, tx) = serve_events().await?;
+    let mut events = EventsClient::connect(&client).await?;
+    let filter = EventsFilter {
+        types: vec!["QuestionsChanged".to_string()],
+        ..Default::default()
+    };
+    events.subscribe(&filter).await?;
+    // give the server some time to process the subscription
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    tx.send(Event::LocaleChanged {
+        locale: "es_ES.UTF-8".to_string(),
+    })?;
+    tx.send(Event::QuestionsChanged)?;
+
+    let event = events.receive().await?;
+    assert_eq!(event, Some(ClientEvent::QuestionsChanged));
+    Ok(())
+}
+
+#[test]
+async fn test_events_replay() -> Result<(), Box<dyn Error>> {
+    let (client, tx) = serve_events().await?;
+    let mut events = EventsClient::connect(&client).await?;
+    tx.send(Event::QuestionsChanged)?;
+    tx.send(Event::QuestionsChanged)?;
+    events.receive().await?;
+    assert_eq!(events.last_id(), Some(1));
+
+    let mut events = EventsClient::connect_since(&client, 1).await?;
+    let event = events.receive().await?;
+    assert_eq!(event, Some(ClientEvent::QuestionsChanged));
+    assert_eq!(events.last_id(), Some(2));
+
+    for _ in 0..3 {
+        tx.send(Event::QuestionsChanged)?;
+    }
+    events.receive().await?;
+    events.receive().await?;
+    events.receive().await?;
+    assert_eq!(events.last_id(), Some(5));
+
+    let mut events = EventsClient::connect_since(&client, 1).await?;
+    let event = events.receive().await?;
+    assert_eq!(event, Some(ClientEvent::ResyncNeeded));
+    Ok(())
+}
+
+#[test]
+async fn test_events_stream() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx.clone(), public_dir())
+        .with_config(config)
+        .build();
+
+    let request = Request::builder()
+        .uri("/api/events")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let request = Request::builder()
+        .uri("/api/events")
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    tx.send(Event::QuestionsChanged)?;
+    let mut body = response.into_body();
+    let frame = body.frame().await.unwrap()?;
+    let data = String::from_utf8(frame.into_data().unwrap().to_vec())?;
+    assert_eq!(
+        data,
+        "event: QuestionsChanged\nid: 1\ndata: {\"id\":1,\"type\":\"QuestionsChanged\"}\n\n"
+    );
+    Ok(())
+}